@@ -1,81 +1,425 @@
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, fs, path::PathBuf};
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use crate::util::{now_utc, iso};
+use time::{Duration, OffsetDateTime, UtcOffset};
+use crate::util::{now_utc, hm, iso, parse_iso};
 
+/// Running sessions, keyed by tag: `{"train": {"start": "...", ...}}`.
+/// Older files are upgraded on load (see `Legacy`).
 #[derive(Default, Serialize, Deserialize)]
+#[serde(from = "Legacy")]
 struct Active {
-    #[serde(skip_serializing_if="Option::is_none")]
-    train: Option<String>,
-    #[serde(skip_serializing_if="Option::is_none")]
-    battle: Option<String>,
+    #[serde(flatten)]
+    sessions: HashMap<String, ActiveSession>,
 }
 
-fn path() -> PathBuf { PathBuf::from(".blaze/active.json") }
+/// One running session in active.json.
+#[derive(Clone, Serialize, Deserialize)]
+struct ActiveSession {
+    start: String,
+    /// From `start --note`; `stop --note` replaces it.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    note: Option<String>,
+    /// ISO end planned with `start --for`.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    planned_end: Option<String>,
+    /// Set while the session is currently paused.
+    #[serde(default, skip_serializing_if="Option::is_none")]
+    paused_since: Option<String>,
+    /// Seconds already spent paused (closed intervals).
+    #[serde(default)]
+    paused_total_secs: i64,
+}
+
+impl ActiveSession {
+    fn new(start: String) -> Self {
+        ActiveSession { start, note: None, planned_end: None, paused_since: None, paused_total_secs: 0 }
+    }
+
+    /// Seconds paused up to `until`, counting a pause that is still open.
+    fn paused_secs(&self, until: OffsetDateTime) -> Result<i64> {
+        let open = match &self.paused_since {
+            Some(since) => (until - parse_iso(since)?).whole_seconds().max(0),
+            None => 0,
+        };
+        Ok(self.paused_total_secs + open)
+    }
+}
+
+/// active.json as older versions wrote it, `{"train": "<start ISO>"}` with pauses and
+/// planned ends in top-level maps, or already in the per-tag object form.
+#[derive(Deserialize)]
+struct Legacy {
+    #[serde(default)]
+    paused: HashMap<String, LegacyPause>,
+    #[serde(default)]
+    planned_end: HashMap<String, String>,
+    #[serde(flatten)]
+    sessions: HashMap<String, LegacySession>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LegacySession {
+    Start(String),
+    Session(ActiveSession),
+}
+
+#[derive(Deserialize)]
+struct LegacyPause {
+    since: Option<String>,
+    #[serde(default)]
+    total_secs: i64,
+}
+
+impl From<Legacy> for Active {
+    fn from(mut old: Legacy) -> Self {
+        let sessions = old.sessions.into_iter().map(|(tag, s)| {
+            let mut s = match s {
+                LegacySession::Session(s) => s,
+                LegacySession::Start(start) => ActiveSession::new(start),
+            };
+            if let Some(p) = old.paused.remove(&tag) {
+                s.paused_since = p.since;
+                s.paused_total_secs = p.total_secs;
+            }
+            if let Some(end) = old.planned_end.remove(&tag) { s.planned_end = Some(end); }
+            (tag, s)
+        });
+        Active { sessions: sessions.collect() }
+    }
+}
+
+/// Top-level keys of the old active.json layout; not usable as tags.
+const RESERVED_KEYS: [&str; 2] = ["paused", "planned_end"];
+
+fn path() -> PathBuf { crate::store::dir().join("active.json") }
 
 fn load() -> Result<Active> {
     if !path().exists() { return Ok(Active::default()); }
     let s = fs::read_to_string(path())?;
     if s.trim().is_empty() { return Ok(Active::default()); }
-    Ok(serde_json::from_str(&s)?)
+    match serde_json::from_str(&s) {
+        Ok(a) => Ok(a),
+        Err(e) => {
+            // don't lock the user out: keep the bad file for inspection and start clean
+            let bad = crate::store::dir().join("active.json.bad");
+            fs::rename(path(), &bad)?;
+            crate::util::warn(format_args!("active.json was corrupt ({e}); moved to {} and reset", bad.display()));
+            Ok(Active::default())
+        }
+    }
 }
 
 fn save(a: &Active) -> Result<()> {
-    let tmp = ".blaze/active.json.tmp";
-    fs::write(tmp, serde_json::to_string_pretty(a)?)?;
-    fs::rename(tmp, path())?;
+    let tmp = crate::store::dir().join("active.json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(a)?)?;
+    fs::rename(&tmp, path())?;
     Ok(())
 }
 
-pub fn start(tag: &str) -> Result<()> {
+/// Advisory `.lock` file held across load -> mutate -> save; removed on drop.
+pub(crate) struct Lock(PathBuf);
+
+impl Lock {
+    /// Locks older than this are leftovers from a crashed run.
+    const STALE_SECS: u64 = 60;
+
+    pub(crate) fn acquire() -> Result<Lock> {
+        let p = crate::store::dir().join(".lock");
+        for _ in 0..2 {
+            match fs::OpenOptions::new().write(true).create_new(true).open(&p) {
+                Ok(_) => return Ok(Lock(p)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let age = fs::metadata(&p).ok()
+                        .and_then(|m| m.modified().ok())
+                        .and_then(|t| t.elapsed().ok())
+                        .map(|d| d.as_secs());
+                    if age.is_some_and(|a| a < Self::STALE_SECS) { break; }
+                    let _ = fs::remove_file(&p);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(anyhow!("another blazectl instance is running (remove {} if not)", p.display()))
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) { let _ = fs::remove_file(&self.0); }
+}
+
+/// The one tag check: trimmed, lowercased (`Train ` == `train`), non-empty and not reserved.
+pub fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() { return Err(anyhow!("empty tag (e.g. train, battle)")); }
+    if RESERVED_KEYS.contains(&tag.as_str()) { return Err(anyhow!("`{tag}` is reserved, pick another tag")); }
+    Ok(tag)
+}
+
+/// Loud hint for a session on the clock longer than config `max_session_hours`.
+fn warn_if_abandoned(tag: &str, elapsed: Duration) {
+    let max = crate::config::get().max_session_hours;
+    if elapsed.whole_seconds() > max as i64 * 3600 {
+        eprintln!(
+            "{}: {tag} has been running for {} (max_session_hours = {max}). \
+             Forgot it? `blazectl stop {tag}` or `blazectl cancel {tag}`.",
+            crate::util::paint(crate::util::Style::Yellow, "WARNING"),
+            hm(elapsed.whole_seconds()),
+        );
+    }
+}
+
+/// A session `start` put on the clock.
+pub struct Started {
+    pub start: String,
+    /// Sessions auto-stopped (config `auto_switch`) for it, to be logged.
+    pub stopped: Vec<crate::store::Entry>,
+}
+
+/// Start `tag` now, or at a backdated RFC3339 `at`, optionally planned to stop `for_secs`
+/// later (see `finish_due`) and with a `note` for the logged entry. With config
+/// `auto_switch`, other running sessions are stopped at that moment and returned for
+/// logging. `None` if `tag` was already running.
+pub fn start(tag: &str, at: Option<&str>, for_secs: Option<i64>, note: Option<String>) -> Result<Option<Started>> {
+    let tag = &normalize_tag(tag)?;
+    let _lock = Lock::acquire()?;
     let mut a = load()?;
-    let now = iso(now_utc());
-    match tag {
-        "train" => {
-            if a.train.is_some() { println!("Already running: train since {}", a.train.as_ref().unwrap()); return Ok(()); }
-            // auto-stop battle if running
-            if a.battle.is_some() { println!("Auto-stop battle before starting train. Run `blazectl stop battle` first."); }
-            a.train = Some(now);
+    let now = now_utc();
+    for (other, s) in &a.sessions {
+        warn_if_abandoned(other, now - parse_iso(&s.start)? - Duration::seconds(s.paused_secs(now)?));
+    }
+    if let Some(s) = a.sessions.get(tag) {
+        crate::util::info(format_args!("Already running: {tag} since {}", s.start));
+        return Ok(None);
+    }
+    let start = match at {
+        None => now,
+        Some(s) => {
+            let t = parse_iso(s)?.to_offset(UtcOffset::UTC);
+            if t > now { return Err(anyhow!("--at {s} is in the future")); }
+            for (other, running) in &a.sessions {
+                if t < parse_iso(&running.start)? {
+                    return Err(anyhow!("--at {s} is before the active {other} session ({})", running.start));
+                }
+            }
+            t
         }
-        "battle" => {
-            if a.battle.is_some() { println!("Already running: battle since {}", a.battle.as_ref().unwrap()); return Ok(()); }
-            if a.train.is_some() { println!("Auto-stop train before starting battle. Run `blazectl stop train` first."); }
-            a.battle = Some(now);
+    };
+    // sessions are exclusive unless config `allow_concurrent`
+    let cfg = crate::config::get();
+    let mut stopped = Vec::new();
+    if !cfg.allow_concurrent {
+        let mut others: Vec<String> = a.sessions.keys().cloned().collect();
+        others.sort();
+        for other in others {
+            if cfg.auto_switch {
+                if let Some(e) = close(&mut a, &other, start, 0)? {
+                    crate::util::info(format_args!("Auto-stopped {other} ({}) before starting {tag}.", hm(e.duration.whole_seconds())));
+                    stopped.push(e);
+                }
+            } else {
+                crate::util::info(format_args!("{other} is still running; `blazectl stop {other}` to log it (or set auto_switch = true)."));
+            }
         }
-        _ => return Err(anyhow!("unknown tag: {tag} (use train|battle)")),
     }
-    save(&a)
+    let mut session = ActiveSession::new(iso(start));
+    session.note = note;
+    session.planned_end = for_secs.map(|secs| iso(start + Duration::seconds(secs)));
+    a.sessions.insert(tag.to_string(), session);
+    save(&a)?;
+    Ok(Some(Started { start: iso(start), stopped }))
 }
 
-pub fn stop(tag: &str) -> Result<Option<crate::store::Entry>> {
+pub fn pause(tag: &str) -> Result<()> {
+    pause_at(tag, now_utc())
+}
+
+/// `pause` as of `since` (clamped to the session start), e.g. when idling began.
+pub fn pause_at(tag: &str, since: OffsetDateTime) -> Result<()> {
+    let tag = &normalize_tag(tag)?;
+    let _lock = Lock::acquire()?;
     let mut a = load()?;
-    let end = now_utc();
+    let Some(s) = a.sessions.get_mut(tag) else {
+        crate::util::info(format_args!("No active `{tag}` session."));
+        return Ok(());
+    };
+    if let Some(since) = &s.paused_since {
+        crate::util::info(format_args!("Already paused: {tag} since {since}"));
+        return Ok(());
+    }
+    s.paused_since = Some(iso(since.max(parse_iso(&s.start)?)));
+    save(&a)
+}
 
-    let (start_opt, _clear_train, _clear_battle) = match tag {
-        "train"  => (a.train.take(), true,  false),
-        "battle" => (a.battle.take(), false, true),
-        _ => return Err(anyhow!("unknown tag: {tag} (use train|battle)")),
+pub fn resume(tag: &str) -> Result<()> {
+    let tag = &normalize_tag(tag)?;
+    let _lock = Lock::acquire()?;
+    let mut a = load()?;
+    let Some(s) = a.sessions.get_mut(tag).filter(|s| s.paused_since.is_some()) else {
+        crate::util::info(format_args!("No paused `{tag}` session."));
+        return Ok(());
     };
+    s.paused_total_secs = s.paused_secs(now_utc())?;
+    s.paused_since = None;
+    save(&a)
+}
 
-    match start_opt {
-        None => Ok(None),
-        Some(start_iso) => {
-            save(&a)?;
-            let start = crate::util::parse_iso(&start_iso)?;
-            let dur = end - start;
-            Ok(Some(crate::store::Entry {
-                activity: tag.to_string(),
-                start: start_iso,
-                end: crate::util::iso(end),
-                duration: dur,
-            }))
-        }
+/// Take `tag` out of `a` as a finished entry ending at `end` (pauses subtracted, long
+/// sessions capped and durations rounded per config). `None` if it wasn't running; an
+/// error, before any cap/round message, if it was clocked for less than `min_secs`.
+fn close(a: &mut Active, tag: &str, end: OffsetDateTime, min_secs: i64) -> Result<Option<crate::store::Entry>> {
+    let Some(session) = a.sessions.remove(tag) else { return Ok(None); };
+    // a pause still open at stop time counts until `end`
+    let paused_secs = session.paused_secs(end)?;
+    let start = parse_iso(&session.start)?;
+    let mut end = end;
+    let mut dur = end - start - Duration::seconds(paused_secs);
+    let secs = dur.whole_seconds();
+    if secs < min_secs {
+        return Err(anyhow!(
+            "{tag} ran only {secs}s (min_session_seconds = {min_secs}); \
+             `blazectl stop {tag} --force` to log it anyway, or `blazectl cancel {tag}`",
+        ));
+    }
+    let cfg = crate::config::get();
+    let cap = Duration::hours(cfg.max_session_hours as i64);
+    if cfg.cap_long_sessions && dur > cap {
+        crate::util::info(format_args!("Capped {tag} at {} (max_session_hours); it ran {}.", hm(cap.whole_seconds()), hm(dur.whole_seconds())));
+        dur = cap;
+        end = start + Duration::seconds(paused_secs) + cap;
+    }
+    // billing-style rounding changes only the stored duration, not start/end
+    let rounded = cfg.round_session(dur.whole_seconds());
+    if rounded != dur.whole_seconds() {
+        crate::util::info(format_args!("Rounded {tag} from {} to {} (round_minutes = {}).",
+            crate::util::hms(dur.whole_seconds()), crate::util::hms(rounded), cfg.round_minutes));
+        dur = Duration::seconds(rounded);
+    }
+    Ok(Some(crate::store::Entry {
+        activity: tag.to_string(),
+        start: session.start,
+        end: iso(end),
+        duration: dur,
+        note: session.note,
+        v: crate::store::FORMAT_VERSION,
+    }))
+}
+
+/// Sessions whose planned end is at or before `now`, earliest first.
+fn due(a: &Active, now: OffsetDateTime) -> Result<Vec<(String, OffsetDateTime)>> {
+    let mut due = Vec::new();
+    for (tag, s) in &a.sessions {
+        let Some(end) = &s.planned_end else { continue; };
+        let end = parse_iso(end)?;
+        if end <= now { due.push((tag.clone(), end)); }
+    }
+    due.sort();
+    Ok(due)
+}
+
+/// Stop every session whose `start --for` end has passed, at that planned end, and
+/// return the entries for logging.
+pub fn finish_due() -> Result<Vec<crate::store::Entry>> {
+    if !path().exists() { return Ok(Vec::new()); }
+    // every command runs this, so only lock when something is actually due
+    let now = now_utc();
+    if due(&load()?, now)?.is_empty() { return Ok(Vec::new()); }
+    let _lock = Lock::acquire()?;
+    let mut a = load()?;
+    // another process may have stopped them in between
+    let due = due(&a, now)?;
+    if due.is_empty() { return Ok(Vec::new()); }
+    let mut done = Vec::new();
+    for (tag, end) in due {
+        if let Some(e) = close(&mut a, &tag, end, 0)? { done.push(e); }
     }
+    save(&a)?;
+    Ok(done)
 }
 
-pub fn status() -> Result<Option<(String, String)>> {
-    let a = load()?;
-    if let Some(s) = a.train { return Ok(Some(("train".into(), s))); }
-    if let Some(s) = a.battle { return Ok(Some(("battle".into(), s))); }
-    Ok(None)
+/// Stop `tag` and return its entry. Sessions shorter than config `min_session_seconds`
+/// are left running with an error unless `force`.
+pub fn stop(tag: &str, force: bool) -> Result<Option<crate::store::Entry>> {
+    let tag = &normalize_tag(tag)?;
+    let _lock = Lock::acquire()?;
+    let mut a = load()?;
+    let min = if force { 0 } else { crate::config::get().min_session_seconds as i64 };
+    let Some(entry) = close(&mut a, tag, now_utc(), min)? else { return Ok(None); };
+    save(&a)?;
+    Ok(Some(entry))
+}
+
+/// Drop a running session without logging it. Returns whether one was running.
+pub fn cancel(tag: &str) -> Result<bool> {
+    let tag = &normalize_tag(tag)?;
+    let _lock = Lock::acquire()?;
+    let mut a = load()?;
+    if a.sessions.remove(tag).is_none() { return Ok(false); }
+    save(&a)?;
+    Ok(true)
+}
+
+/// Move a running `old` session (pauses, note and all) to `new`. Returns whether one was running.
+pub fn rename_tag(old: &str, new: &str) -> Result<bool> {
+    let (old, new) = (&normalize_tag(old)?, &normalize_tag(new)?);
+    let _lock = Lock::acquire()?;
+    let mut a = load()?;
+    let Some(session) = a.sessions.remove(old) else { return Ok(false); };
+    if a.sessions.contains_key(new) {
+        return Err(anyhow!("both {old} and {new} are running; stop one first"));
+    }
+    a.sessions.insert(new.to_string(), session);
+    save(&a)?;
+    Ok(true)
+}
+
+/// A running session as reported by `status`.
+pub struct Running {
+    pub tag: String,
+    pub start: String,
+    pub paused_since: Option<String>,
+    /// From `start --for`.
+    pub planned_end: Option<String>,
+    /// From `start --note`.
+    pub note: Option<String>,
+    /// Time on the clock so far, excluding pauses.
+    pub elapsed: Duration,
+}
+
+/// Every running session, oldest first.
+pub fn running() -> Result<Vec<Running>> {
+    let now = now_utc();
+    let mut out = Vec::new();
+    for (tag, s) in load()?.sessions {
+        let elapsed = now - parse_iso(&s.start)? - Duration::seconds(s.paused_secs(now)?);
+        out.push(Running {
+            tag,
+            start: s.start,
+            paused_since: s.paused_since,
+            planned_end: s.planned_end,
+            note: s.note,
+            elapsed,
+        });
+    }
+    out.sort_by(|x, y| x.start.cmp(&y.start));
+    Ok(out)
+}
+
+/// (tag, seconds) of each running session that fall on today's local date.
+pub fn live_today() -> Result<Vec<(String, i64)>> {
+    let since_midnight = (now_utc() - crate::util::day_start(crate::util::today())).whole_seconds();
+    Ok(running()?
+        .into_iter()
+        .map(|r| (r.tag, r.elapsed.whole_seconds().clamp(0, since_midnight)))
+        .collect())
+}
+
+/// Every running session, oldest first; warns about any that look abandoned.
+pub fn status() -> Result<Vec<Running>> {
+    let rs = running()?;
+    for r in &rs {
+        warn_if_abandoned(&r.tag, r.elapsed);
+    }
+    Ok(rs)
 }
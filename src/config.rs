@@ -0,0 +1,348 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::OnceLock};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Optional overrides from `config.toml` in the data dir (`.blaze/`). Every key is optional;
+/// missing keys keep the built-in defaults.
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// day boundaries and printed times: `UTC`, `+HH:MM` or an IANA name; logs stay UTC
+    pub timezone: String,
+    /// generated markdown file, relative to the repo root
+    pub readme_path: String,
+    /// directory for the chart images, relative to the repo root
+    pub asset_dir: String,
+    /// `light` | `dark` palette for the SVGs
+    pub theme: String,
+    /// days in the trend line's centered moving average: smaller reacts faster to
+    /// recent days, larger irons out one-off spikes
+    pub trend_window: usize,
+    /// spline samples between trend control points
+    pub trend_samples: usize,
+    /// activity chart: `total` (one series + trend) | `per_tag` (one line per tag)
+    pub chart_mode: String,
+    /// days shown in the activity chart (`render-readme --chart-days` wins)
+    pub chart_days: u32,
+    /// activity chart image: `svg` | `png` (`render-readme --format` wins)
+    pub chart_format: String,
+    /// running total of hours on a right-hand axis
+    pub show_cumulative: bool,
+    pub chart_width: u32,
+    pub chart_height: u32,
+    /// hex colors (`#rrggbb`) overriding the theme
+    pub accent_color: Option<String>,
+    pub trend_color: Option<String>,
+    pub bg_color: Option<String>,
+    /// let several tags run at once without the stop-the-other warning
+    pub allow_concurrent: bool,
+    /// `start` stops (and logs) other running sessions instead of only warning
+    pub auto_switch: bool,
+    /// `stop` refuses shorter sessions unless `--force` (0 = off)
+    pub min_session_seconds: u64,
+    /// `start`/`status` warn about sessions running longer than this
+    pub max_session_hours: u64,
+    /// `stop` logs at most `max_session_hours` of a forgotten session
+    pub cap_long_sessions: bool,
+    /// round each logged duration to a multiple of this many minutes (0 = off). This
+    /// changes the *stored* duration; start/end stay as clocked
+    pub round_minutes: u64,
+    /// `nearest` | `up` | `down`, for `round_minutes`
+    pub round_mode: String,
+    /// cache per-day aggregates in `index.json` (rebuilt per changed month file; for long
+    /// histories). `render-readme --rebuild-index` starts it over
+    pub index_cache: bool,
+    /// hours between auto-commits (env BLAZE_COMMIT_INTERVAL_HOURS wins)
+    pub commit_interval_hours: u64,
+    /// `daily` (every `commit_interval_hours`) | `per_session` (whenever a `stop`, `log`, ...
+    /// re-renders the README)
+    pub commit_mode: String,
+    /// paths (relative to the repo root) staged by auto-commit; `{data}`, `{readme}` and
+    /// `{assets}` expand to the data dir, `readme_path` and `asset_dir`
+    pub commit_paths: Vec<String>,
+    /// only auto-commit while this branch is checked out
+    pub commit_branch: Option<String>,
+    /// `git push` after each auto-commit
+    pub auto_push: bool,
+    /// auto-commit message; placeholders: {date}, {total_today}, {streak}
+    pub commit_message_template: String,
+    /// first day of the week for the weekly table, weekly goals and heatmap rows:
+    /// `monday` (ISO weeks) | `sunday`
+    pub week_start: String,
+    /// `[goals]`: `<tag>_daily_minutes` / `<tag>_weekly_minutes` (tag `total` = all tags)
+    pub goals: HashMap<String, u64>,
+    /// `[tags.<tag>]`: how a tag shows up in the README and charts
+    pub tags: HashMap<String, TagStyle>,
+}
+
+/// One `[tags.<tag>]` section; every key is optional.
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TagStyle {
+    /// display name instead of the capitalized tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `#rrggbb` for the tag's per-tag chart line (otherwise a palette color)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// shown before the name in the README
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            timezone: "UTC".into(),
+            readme_path: "README.md".into(),
+            asset_dir: "assets".into(),
+            theme: "dark".into(),
+            trend_window: 8,
+            trend_samples: 50,
+            chart_mode: "total".into(),
+            chart_days: 75,
+            chart_format: "svg".into(),
+            show_cumulative: false,
+            chart_width: 900,
+            chart_height: 240,
+            accent_color: None,
+            trend_color: None,
+            bg_color: None,
+            allow_concurrent: false,
+            auto_switch: false,
+            min_session_seconds: 0,
+            max_session_hours: 12,
+            cap_long_sessions: false,
+            round_minutes: 0,
+            round_mode: "nearest".into(),
+            index_cache: false,
+            commit_interval_hours: 24,
+            commit_mode: "daily".into(),
+            commit_paths: ["{readme}", "{data}/active.json", "{data}/", "{assets}/"]
+                .map(String::from).to_vec(),
+            commit_branch: None,
+            auto_push: false,
+            commit_message_template: "blazectl: update ({date})".into(),
+            week_start: "monday".into(),
+            goals: HashMap::new(),
+            tags: HashMap::new(),
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+fn path() -> PathBuf { crate::store::dir().join("config.toml") }
+
+/// Read and validate the config file once at startup.
+pub fn load() -> Result<()> {
+    let mut cfg = if path().exists() {
+        parse(&fs::read_to_string(path())?).map_err(|e| anyhow!("{}: {e}", path().display()))?
+    } else {
+        Config::default()
+    };
+    if let Ok(v) = std::env::var("BLAZE_COMMIT_INTERVAL_HOURS") {
+        cfg.commit_interval_hours = v.trim().parse()
+            .map_err(|_| anyhow!("BLAZE_COMMIT_INTERVAL_HOURS must be a positive integer, got {v:?}"))?;
+        if cfg.commit_interval_hours == 0 {
+            return Err(anyhow!("BLAZE_COMMIT_INTERVAL_HOURS must be a positive integer"));
+        }
+    }
+    crate::util::set_zone(crate::util::parse_zone(&cfg.timezone)?);
+    let _ = CONFIG.set(cfg);
+    Ok(())
+}
+
+/// Deserialize and validate config.toml text.
+fn parse(s: &str) -> Result<Config> {
+    let mut cfg: Config = toml::from_str(s)?;
+    for c in [&cfg.accent_color, &cfg.trend_color, &cfg.bg_color].into_iter().flatten() {
+        parse_hex(c)?;
+    }
+    // `[tags.Train]` styles the `train` tag
+    let mut tags = HashMap::new();
+    for (tag, style) in std::mem::take(&mut cfg.tags) {
+        if let Some(c) = &style.color { parse_hex(c).map_err(|e| anyhow!("tags.{tag}.color: {e}"))?; }
+        let key = crate::active::normalize_tag(&tag).map_err(|e| anyhow!("tags.{tag}: {e}"))?;
+        if tags.insert(key, style).is_some() { return Err(anyhow!("tags.{tag} is configured twice")); }
+    }
+    cfg.tags = tags;
+    if !matches!(cfg.theme.as_str(), "light" | "dark") {
+        return Err(anyhow!("unknown theme: {} (use light|dark)", cfg.theme));
+    }
+    if cfg.commit_interval_hours == 0 {
+        return Err(anyhow!("commit_interval_hours must be a positive integer"));
+    }
+    for (k, v) in &cfg.goals {
+        if !(k.ends_with("_daily_minutes") || k.ends_with("_weekly_minutes")) {
+            return Err(anyhow!("goals.{k}: expected <tag>_daily_minutes or <tag>_weekly_minutes"));
+        }
+        if *v == 0 { return Err(anyhow!("goals.{k} must be positive")); }
+    }
+    if !matches!(cfg.chart_mode.as_str(), "total" | "per_tag") {
+        return Err(anyhow!("unknown chart_mode: {} (use total|per_tag)", cfg.chart_mode));
+    }
+    for (key, p) in [("readme_path", &cfg.readme_path), ("asset_dir", &cfg.asset_dir)] {
+        let path = std::path::Path::new(p);
+        if p.trim().is_empty() || path.is_absolute()
+            || path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+            return Err(anyhow!("{key} must be a relative path inside the repo, got {p:?}"));
+        }
+    }
+    if cfg.commit_paths.iter().any(|p| p.trim().is_empty()) {
+        return Err(anyhow!("commit_paths entries must not be empty"));
+    }
+    if cfg.max_session_hours == 0 {
+        return Err(anyhow!("max_session_hours must be positive"));
+    }
+    if !matches!(cfg.chart_format.as_str(), "svg" | "png") {
+        return Err(anyhow!("unknown chart_format: {} (use svg|png)", cfg.chart_format));
+    }
+    if !matches!(cfg.round_mode.as_str(), "nearest" | "up" | "down") {
+        return Err(anyhow!("unknown round_mode: {} (use nearest|up|down)", cfg.round_mode));
+    }
+    if !matches!(cfg.commit_mode.as_str(), "daily" | "per_session") {
+        return Err(anyhow!("unknown commit_mode: {} (use daily|per_session)", cfg.commit_mode));
+    }
+    if !matches!(cfg.week_start.as_str(), "monday" | "sunday") {
+        return Err(anyhow!("unknown week_start: {} (use monday|sunday)", cfg.week_start));
+    }
+    if cfg.chart_days == 0 {
+        return Err(anyhow!("chart_days must be positive"));
+    }
+    if cfg.trend_window == 0 || cfg.trend_samples == 0 {
+        return Err(anyhow!("trend_window and trend_samples must be positive"));
+    }
+    crate::util::parse_zone(&cfg.timezone)?;
+    Ok(cfg)
+}
+
+/// Keys without a default value (so missing from `list` until set).
+const OPTIONAL_KEYS: [&str; 4] = ["accent_color", "trend_color", "bg_color", "commit_branch"];
+
+/// The loaded config as a TOML table, for `config get` / `config list`.
+fn effective() -> Result<toml::Table> {
+    Ok(toml::Table::try_from(get())?)
+}
+
+/// `blazectl config list`: every setting in effect, defaults included.
+pub fn list() -> Result<String> {
+    Ok(toml::to_string(&effective()?)?.trim_end().to_string())
+}
+
+/// `blazectl config get <key>`; `goals.<name>` reaches into `[goals]`.
+pub fn get_key(key: &str) -> Result<String> {
+    let table = effective()?;
+    let value = match key.split_once('.') {
+        Some((section, sub)) => table.get(section).and_then(|t| t.get(sub)),
+        None => table.get(key),
+    };
+    match value {
+        Some(toml::Value::String(s)) => Ok(s.clone()),
+        Some(v) => Ok(v.to_string()),
+        None if known(key) => Err(anyhow!("{key} is not set")),
+        None => Err(anyhow!("unknown config key: {key}")),
+    }
+}
+
+fn known(key: &str) -> bool {
+    let defaults = toml::Table::try_from(Config::default()).unwrap_or_default();
+    match key.split_once('.') {
+        Some(("goals", sub)) => !sub.is_empty(),
+        Some(_) => false,
+        None => key != "goals" && (defaults.contains_key(key) || OPTIONAL_KEYS.contains(&key)),
+    }
+}
+
+/// `blazectl config set <key> <value>`: edits config.toml in place (comments and order
+/// kept), typed like the built-in default, and refuses anything `load` would reject.
+pub fn set_key(key: &str, raw: &str) -> Result<()> {
+    if !known(key) { return Err(anyhow!("unknown config key: {key}")); }
+    let text = if path().exists() { fs::read_to_string(path())? } else { String::new() };
+    let mut doc: toml_edit::DocumentMut = text.parse().map_err(|e| anyhow!("{}: {e}", path().display()))?;
+
+    let default = toml::Table::try_from(Config::default())?;
+    let bad = |what: &str| anyhow!("{key} expects {what}, got {raw:?}");
+    let value: toml_edit::Value = match key.split_once('.').map_or(default.get(key), |_| None) {
+        Some(toml::Value::Integer(_)) => raw.trim().parse::<i64>().map_err(|_| bad("an integer"))?.into(),
+        Some(toml::Value::Boolean(_)) => raw.trim().parse::<bool>().map_err(|_| bad("true or false"))?.into(),
+        Some(toml::Value::Array(_)) => raw.parse().map_err(|_| bad(r#"an array like ["a", "b"]"#))?,
+        Some(_) => raw.into(),
+        // goals.*
+        None if key.contains('.') => raw.trim().parse::<i64>().map_err(|_| bad("minutes"))?.into(),
+        None => raw.into(),
+    };
+    match key.split_once('.') {
+        Some((section, sub)) => {
+            let t = doc.entry(section).or_insert(toml_edit::table());
+            let t = t.as_table_like_mut().ok_or_else(|| anyhow!("[{section}] in config.toml is not a table"))?;
+            t.insert(sub, toml_edit::value(value));
+        }
+        None => { doc.insert(key, toml_edit::value(value)); }
+    }
+
+    let out = doc.to_string();
+    parse(&out)?;
+    crate::store::write_file(&path(), &out)
+}
+
+/// The loaded config (defaults if `load` was never called).
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::default)
+}
+
+impl Config {
+    /// Daily goal in seconds for `tag` (`total` = all tags).
+    pub fn daily_goal(&self, tag: &str) -> Option<i64> {
+        self.goals.get(&format!("{tag}_daily_minutes")).map(|m| *m as i64 * 60)
+    }
+
+    /// Weekly goal in seconds; falls back to 7x the daily goal.
+    pub fn weekly_goal(&self, tag: &str) -> Option<i64> {
+        self.goals.get(&format!("{tag}_weekly_minutes")).map(|m| *m as i64 * 60)
+            .or_else(|| self.daily_goal(tag).map(|d| d * 7))
+    }
+
+    /// `secs` rounded per `round_minutes` / `round_mode` (unchanged when off).
+    pub fn round_seconds(&self, secs: i64) -> i64 {
+        let step = self.round_minutes as i64 * 60;
+        if step == 0 { return secs; }
+        let down = secs.div_euclid(step) * step;
+        match self.round_mode.as_str() {
+            "up" if down < secs => down + step,
+            "nearest" if secs - down >= step - (secs - down) => down + step,
+            _ => down,
+        }
+    }
+
+    /// `round_seconds` for a logged session: one that ran never rounds down to 0s (it
+    /// already passed `min_session_seconds`), it gets at least one step.
+    pub fn round_session(&self, secs: i64) -> i64 {
+        let rounded = self.round_seconds(secs);
+        if secs > 0 { rounded.max(self.round_minutes as i64 * 60) } else { rounded }
+    }
+
+    /// `[tags.<tag>]` settings, if any.
+    pub fn tag_style(&self, tag: &str) -> Option<&TagStyle> { self.tags.get(tag) }
+
+    /// Tags that have any goal configured, sorted.
+    pub fn goal_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.goals.keys()
+            .filter_map(|k| k.strip_suffix("_daily_minutes").or_else(|| k.strip_suffix("_weekly_minutes")))
+            .map(str::to_string)
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+}
+
+/// `#rrggbb` (or `rrggbb`) -> (r, g, b)
+pub fn parse_hex(s: &str) -> Result<(u8, u8, u8)> {
+    let h = s.trim().trim_start_matches('#');
+    if h.len() != 6 || !h.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("invalid color {s:?} (expected #rrggbb)"));
+    }
+    let byte = |i: usize| u8::from_str_radix(&h[i..i + 2], 16).unwrap_or(0);
+    Ok((byte(0), byte(2), byte(4)))
+}
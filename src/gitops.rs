@@ -1,9 +1,63 @@
-use anyhow::Result;
-use std::process::Command;
+use anyhow::{anyhow, Result};
+use std::process::{Command, Output};
+use std::sync::{OnceLock, atomic::{AtomicBool, Ordering}};
 use std::time::{SystemTime, UNIX_EPOCH};
+use crate::util::trace;
+
+static PUSH: AtomicBool = AtomicBool::new(false);
+
+/// `--push`: push after auto-commit even if config `auto_push` is off.
+pub fn set_push(on: bool) { PUSH.store(on, Ordering::Relaxed); }
+
+fn head() -> Option<String> {
+    let out = git().args(["rev-parse","HEAD"]).output().ok()?;
+    if !out.status.success() { return None; }
+    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+/// `git` run from the tracked repo root (parent of the data dir).
+fn git() -> Command {
+    let mut c = Command::new("git");
+    c.current_dir(crate::store::root());
+    c
+}
+
+/// Run `git <args>` capturing its output, tracing the call and its exit status under `--verbose`.
+fn run(args: &[&str]) -> Result<Output> {
+    let cmd = format!("git {}", args.join(" "));
+    let out = git().args(args).output().map_err(|e| anyhow!("{cmd}: could not run git ({e})"))?;
+    trace(format_args!("{cmd} -> {}", out.status));
+    Ok(out)
+}
+
+/// `run`, turning a non-zero exit into an error naming the step and git's message.
+fn step(args: &[&str]) -> Result<Output> {
+    let out = run(args)?;
+    if !out.status.success() {
+        let msg = String::from_utf8_lossy(&out.stderr);
+        let msg = if msg.trim().is_empty() { String::from_utf8_lossy(&out.stdout) } else { msg };
+        return Err(anyhow!("`git {}` failed ({}): {}", args.join(" "), out.status, msg.trim()));
+    }
+    Ok(out)
+}
+
+/// Whether a `git` binary can be run at all; probed once per process, which is also
+/// the one time the user is told it's missing.
+fn git_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| match Command::new("git").arg("--version").output() {
+        Ok(_) => true,
+        Err(e) => {
+            if !crate::util::quiet() {
+                crate::util::error(format_args!("note: git is not installed ({e}); tracking stays local and auto-commit is skipped."));
+            }
+            false
+        }
+    })
+}
 
 fn last_commit_ts() -> Option<i64> {
-    let out = Command::new("git").args(["log","-1","--format=%ct"]).output().ok()?;
+    let out = git().args(["log","-1","--format=%ct"]).output().ok()?;
     if !out.status.success() { return None; }
     let s = String::from_utf8_lossy(&out.stdout).trim().to_string();
     s.parse::<i64>().ok()
@@ -13,21 +67,109 @@ fn now_ts() -> i64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
 }
 
-pub fn auto_commit_if_due() -> Result<()> {
+/// Commit tracked data once `commit_interval_hours` have passed since the
+/// last commit, or every time with `commit_mode = "per_session"`; `force`
+/// skips the interval check.
+pub fn auto_commit_if_due(force: bool) -> Result<()> {
     // only if in a git repo
-    if !std::path::Path::new(".git").exists() { return Ok(()); }
+    let root = crate::store::root();
+    if !root.join(".git").exists() {
+        trace(format_args!("no git repo at {}; skipping auto-commit", root.display()));
+        return Ok(());
+    }
+    if !git_available() { return Ok(()); }
 
-    let due = match last_commit_ts() {
-        Some(ts) => now_ts() - ts >= 24*3600,
+    let interval = crate::config::get().commit_interval_hours as i64 * 3600;
+    let per_session = crate::config::get().commit_mode == "per_session";
+    let due = force || per_session || match last_commit_ts() {
+        Some(ts) => now_ts() - ts >= interval,
         None => true, // no commits yet
     };
-    if !due { return Ok(()); }
-
-    // add & commit if changes exist
-    let _ = Command::new("git").args(["add","README.md"]).status();
-    let _ = Command::new("git").args(["add",".blaze/active.json"]).status();
-    let _ = Command::new("git").args(["add",".blaze/"]).status();
-    let msg = format!("blazectl: update ({})", chrono::Utc::now().format("%Y-%m-%d UTC"));
-    let _ = Command::new("git").args(["commit","-m",&msg]).status();
+    if !due {
+        trace(format_args!("auto-commit not due (commit_interval_hours = {})", interval / 3600));
+        return Ok(());
+    }
+
+    // never commit onto the wrong branch (or a detached HEAD)
+    if let Some(want) = &crate::config::get().commit_branch {
+        let out = run(&["symbolic-ref","--short","HEAD"])?;
+        let cur = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        if !out.status.success() {
+            return Err(anyhow!("HEAD is detached; commit_branch is {want}, not committing"));
+        }
+        if &cur != want {
+            return Err(anyhow!("on branch {cur} but commit_branch is {want}; not committing (git checkout {want})"));
+        }
+    }
+
+    // add config `commit_paths` & commit if changes exist
+    // data dir relative to the root, e.g. ".blaze"
+    let data = crate::store::dir();
+    let data = data.strip_prefix(&root).unwrap_or(&data).to_string_lossy().into_owned();
+    // a path that fails to stage (e.g. gitignored) shouldn't block the rest
+    let cfg = crate::config::get();
+    let mut paths: Vec<String> = cfg.commit_paths.iter()
+        .map(|p| p.replace("{data}", &data).replace("{readme}", &cfg.readme_path).replace("{assets}", &cfg.asset_dir))
+        .collect();
+    // the README's images must land with it, or GitHub shows stale/missing ones
+    if paths.contains(&cfg.readme_path) {
+        paths.extend(crate::readme::ASSETS.iter().map(|a| format!("{}/{a}", cfg.asset_dir)));
+    }
+    if crate::util::dry_run() {
+        crate::util::say("dry run: would run");
+        for path in paths.iter().filter(|p| root.join(p).exists()) {
+            crate::util::say(format_args!("  git add {path}"));
+        }
+        crate::util::say(format_args!("  git commit -m {:?}", commit_message(&crate::config::get().commit_message_template)));
+        if PUSH.load(Ordering::Relaxed) || crate::config::get().auto_push { crate::util::say("  git push"); }
+        return Ok(());
+    }
+    for path in paths {
+        if !root.join(&path).exists() {
+            // tracked but gone (an empty-state render drops the charts): commit the removal
+            let tracked = run(&["ls-files", "--", &path]).is_ok_and(|o| !o.stdout.is_empty());
+            if tracked {
+                if let Err(e) = step(&["rm", "--cached", "--quiet", "--", &path]) { crate::util::warn(e); }
+            }
+            continue;
+        }
+        if let Err(e) = step(&["add", &path]) { crate::util::warn(e); }
+    }
+    // nothing staged -> no commit, so the interval timer isn't reset
+    let staged = run(&["diff","--cached","--quiet"])?;
+    match staged.status.code() {
+        Some(0) => {
+            trace("nothing staged; no commit");
+            return Ok(());
+        }
+        Some(1) => {}
+        _ => return Err(anyhow!("`git diff --cached --quiet` failed ({})", staged.status)),
+    }
+
+    let before = head();
+    let msg = commit_message(&crate::config::get().commit_message_template);
+    step(&["commit","-m",&msg])?;
+
+    // push only when the commit actually landed
+    let committed = head().is_some() && head() != before;
+    if !committed { return Err(anyhow!("git commit reported success but HEAD did not move")); }
+    trace("committed");
+    if PUSH.load(Ordering::Relaxed) || crate::config::get().auto_push {
+        if let Err(e) = step(&["push"]) {
+            crate::util::warn(format_args!("commit is local only: {e}"));
+        }
+    }
     Ok(())
 }
+
+/// Fill `{date}`, `{total_today}` and `{streak}` in the commit template.
+fn commit_message(template: &str) -> String {
+    let mut msg = template.replace("{date}", &chrono::Utc::now().format("%Y-%m-%d UTC").to_string());
+    if msg.contains("{total_today}") || msg.contains("{streak}") {
+        let (today, streak) = crate::readme::today_and_streak().unwrap_or((0, 0));
+        msg = msg
+            .replace("{total_today}", &crate::util::hm(today))
+            .replace("{streak}", &format!("{streak}d"));
+    }
+    msg
+}
@@ -0,0 +1,102 @@
+use std::{collections::{BTreeMap, HashMap}, fs, path::PathBuf, time::UNIX_EPOCH};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use time::{macros::format_description, Date};
+use crate::readme::{aggregate_entries, hour_totals, Totals};
+use crate::util::trace;
+
+/// Cached aggregates per month file (config `index_cache`), so a stop only re-parses the
+/// month it touched. Everything in it is additive across files.
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    /// config `timezone` the day buckets were cut in
+    timezone: String,
+    files: BTreeMap<String, FileIndex>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileIndex {
+    len: u64,
+    mtime_ns: u64,
+    all_time: Totals,
+    /// `YYYY-MM-DD` -> totals
+    per_day: BTreeMap<String, Totals>,
+    hours: [i64; 24],
+}
+
+fn path() -> PathBuf { crate::store::dir().join("index.json") }
+
+/// `render-readme --rebuild-index`: drop the cache so the next read rebuilds it.
+pub fn remove() -> Result<()> {
+    if path().exists() {
+        fs::remove_file(path())?;
+        trace(format_args!("removed {}", path().display()));
+    }
+    Ok(())
+}
+
+fn stamp(p: &std::path::Path) -> Option<(u64, u64)> {
+    let m = fs::metadata(p).ok()?;
+    let mtime = m.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64;
+    Some((m.len(), mtime))
+}
+
+/// (all-time totals, per-day totals, seconds per hour of day) over every logged entry,
+/// from the cache where a month file is unchanged (same size and mtime).
+pub fn aggregate() -> Result<(Totals, HashMap<Date, Totals>, [i64; 24])> {
+    let cfg = crate::config::get();
+    if !cfg.index_cache {
+        let entries = crate::store::read_all_entries()?;
+        let (all_time, per_day) = aggregate_entries(&entries);
+        return Ok((all_time, per_day, hour_totals(&entries)));
+    }
+
+    let mut index: Index = fs::read_to_string(path()).ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .filter(|i: &Index| i.timezone == cfg.timezone)
+        .unwrap_or_default();
+    index.timezone = cfg.timezone.clone();
+    let mut dirty = false;
+    let mut seen = Vec::new();
+    for file in crate::store::track_files() {
+        let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let Some((len, mtime_ns)) = stamp(&file) else { continue; };
+        seen.push(name.clone());
+        if index.files.get(&name).is_some_and(|f| f.len == len && f.mtime_ns == mtime_ns) { continue; }
+        trace(format_args!("indexing {name}"));
+        let entries = crate::store::read_entries_file(&file);
+        let (all_time, per_day) = aggregate_entries(&entries);
+        let per_day = per_day.into_iter().map(|(d, t)| (d.to_string(), t)).collect();
+        index.files.insert(name, FileIndex { len, mtime_ns, all_time, per_day, hours: hour_totals(&entries) });
+        dirty = true;
+    }
+    let before = index.files.len();
+    index.files.retain(|name, _| seen.contains(name));
+    dirty |= index.files.len() != before;
+    // --dry-run leaves the data dir untouched; the next real run rebuilds the same cache
+    if dirty && !crate::util::dry_run() { save(&index)?; }
+
+    let mut all_time = Totals::default();
+    let mut per_day: HashMap<Date, Totals> = HashMap::new();
+    let mut hours = [0i64; 24];
+    for f in index.files.values() {
+        all_time.merge(&f.all_time);
+        for (d, t) in &f.per_day {
+            let Ok(d) = Date::parse(d, format_description!("[year]-[month]-[day]")) else { continue; };
+            per_day.entry(d).or_default().merge(t);
+        }
+        for (h, s) in hours.iter_mut().zip(f.hours) { *h += s; }
+    }
+    Ok((all_time, per_day, hours))
+}
+
+fn save(index: &Index) -> Result<()> {
+    // a cache, not data: keep it out of auto-commits
+    let ignore = crate::store::dir().join(".gitignore");
+    let current = fs::read_to_string(&ignore).unwrap_or_default();
+    if !current.lines().any(|l| l.trim() == "index.json") {
+        let sep = if current.is_empty() || current.ends_with('\n') { "" } else { "\n" };
+        crate::store::write_file(&ignore, &format!("{current}{sep}index.json\n"))?;
+    }
+    crate::store::write_file(&path(), &serde_json::to_string(index)?)
+}
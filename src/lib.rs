@@ -0,0 +1,51 @@
+//! blazectl as a library: the CLI in `main.rs` is a thin layer over these modules.
+//!
+//! For read-only embedding (dashboards, reports) the top-level functions take a data
+//! directory explicitly and don't touch the working directory:
+//!
+//! ```no_run
+//! let entries = blazectl::load_entries("/home/me/notes/.blaze".as_ref())?;
+//! let (all_time, per_day) = blazectl::aggregate(&entries);
+//! println!("{}s logged, {} tags", all_time.total(), all_time.tags().len());
+//! # let _ = per_day;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! Day boundaries use the zone set with [`util::set_zone`] (UTC unless `config::load` ran).
+
+pub mod active;
+pub mod config;
+pub mod gitops;
+pub mod index;
+pub mod readme;
+pub mod store;
+pub mod util;
+pub mod watch;
+
+use std::{collections::HashMap, path::Path};
+use time::Date;
+
+pub use readme::Totals;
+pub use store::LoggedEntry;
+
+/// Every usable entry in `dir`'s month files (`track-YYYY-MM.jsonl`).
+pub fn load_entries(dir: &Path) -> anyhow::Result<Vec<LoggedEntry>> {
+    store::read_entries_in(dir)
+}
+
+/// (all-time totals, totals per local day); entries spanning midnight are split.
+pub fn aggregate(entries: &[LoggedEntry]) -> (Totals, HashMap<Date, Totals>) {
+    readme::aggregate_entries(entries)
+}
+
+/// The `n` days ending with `today`, oldest first (the README's 7/30/75-day windows).
+pub fn days_back(today: Date, n: i32) -> Vec<Date> {
+    readme::days_back(today, n)
+}
+
+/// (label, current, longest) day streaks as of `today`: "any" activity, each tag, then
+/// configured daily goals.
+pub fn streaks(per_day: &HashMap<Date, Totals>, today: Date) -> Vec<(String, i32, i32)> {
+    let days: Vec<Date> = per_day.keys().copied().collect();
+    readme::streak_rows(per_day, today, &readme::sum_over(per_day, &days))
+}
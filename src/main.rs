@@ -1,75 +1,402 @@
-mod active;
-mod store;
-mod readme;
-mod gitops;
-mod util;
+use blazectl::{active, config, gitops, index, readme, store, util, watch};
+use clap::{CommandFactory, Parser, Subcommand};
 
-use clap::{Parser, Subcommand};
+/// Exit code of `status` and `stop` when no matching session is running (errors exit 1).
+const EXIT_IDLE: i32 = 3;
+/// Exit code of `check-freshness` when the README no longer matches the logs.
+const EXIT_STALE: i32 = 4;
 
 #[derive(Parser)]
-#[command(name="blazectl", version, about="Train/Battle time logger (UTC)")]
+#[command(name="blazectl", version, about="Train/Battle (and custom tag) time logger (stored in UTC; display per config `timezone`)",
+    after_help="Exit codes: 0 ok, 1 error, 3 nothing running (status, stop), 4 README stale (check-freshness)")]
 struct Cli {
+    /// Data directory (also BLAZE_HOME); README/assets/git live in its parent
+    #[arg(long, global = true, value_name = "PATH")]
+    dir: Option<std::path::PathBuf>,
+    /// fsync logs and README/SVG before git (also BLAZE_SYNC=1)
+    #[arg(long, global = true)]
+    sync: bool,
+    /// git push after auto-commit (also config `auto_push`)
+    #[arg(long, global = true)]
+    push: bool,
+    /// Render README/SVGs into a temp dir and print the git commands instead of running them
+    /// (session logs are still written)
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Trace file writes and git commands (with exit status) on stderr
+    #[arg(long, short, global = true)]
+    verbose: bool,
+    /// Only errors, warnings and the output a command was asked for (exit codes unchanged)
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// No ANSI colors (also NO_COLOR=1; off anyway when stdout isn't a terminal)
+    #[arg(long, global = true)]
+    no_color: bool,
+    /// Print one JSON line per start/stop/cancel on stdout, for hook scripts
+    #[arg(long, global = true)]
+    emit_event: bool,
     #[command(subcommand)]
     cmd: Cmd,
 }
 
 #[derive(Subcommand)]
 enum Cmd {
-    /// Start a session: train | battle
-    Start { tag: String },
-    /// Stop a session: train | battle
-    Stop  { tag: String },
-    /// Show active session, if any
-    Status,
+    /// Start a session: train | battle | any custom tag
+    Start {
+        tag: String,
+        /// Backdate the start (RFC3339, e.g. 2024-01-01T10:00:00Z)
+        #[arg(long)]
+        at: Option<String>,
+        /// Stop automatically after this long: 50m, 1h30m, PT50M (checked by the next
+        /// command or a running `watch`)
+        #[arg(long = "for", value_name = "SPAN")]
+        for_span: Option<String>,
+        /// What the session is about (`stop --note` replaces it)
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Stop a session: train | battle | any custom tag (exit 3 if it isn't running)
+    Stop  {
+        tag: String,
+        /// What the session was about
+        #[arg(long)]
+        note: Option<String>,
+        /// Log it even if shorter than config `min_session_seconds`
+        #[arg(long)]
+        force: bool,
+    },
+    /// Discard a running session without logging it
+    Cancel { tag: String },
+    /// Pause a running session; paused time is not logged
+    Pause { tag: String },
+    /// Resume a paused session
+    Resume { tag: String },
+    /// Stop one session and start another, rendering once
+    Switch { from: String, to: String },
+    /// Record a completed session directly
+    Log {
+        tag: String,
+        /// Session start (RFC3339)
+        #[arg(long)]
+        start: String,
+        /// Session end (RFC3339)
+        #[arg(long, conflicts_with = "duration", required_unless_present = "duration")]
+        end: Option<String>,
+        /// Session length instead of --end (ISO-8601, e.g. PT1H30M0S)
+        #[arg(long)]
+        duration: Option<String>,
+        /// What the session was about
+        #[arg(long)]
+        note: Option<String>,
+    },
+    /// Export every entry to one JSON or CSV file
+    Export {
+        #[arg(long, default_value = "json", value_parser = ["json", "csv"])]
+        format: String,
+        /// Output file (stdout if omitted)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Import sessions (tag,start,end[,note] rows); re-running is idempotent
+    Import {
+        #[arg(long, default_value = "csv", value_parser = ["csv"])]
+        format: String,
+        path: std::path::PathBuf,
+    },
+    /// Check logs for malformed or inconsistent entries
+    Doctor {
+        /// Drop/repair bad lines (originals kept as .bak)
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Remove the most recently ended entry
+    Undo,
+    /// Archive all logs and the running session under .blaze/archive/<timestamp>/ and start fresh
+    Reset {
+        /// Really do it (otherwise only says what would be archived)
+        #[arg(long)]
+        yes: bool,
+    },
+    /// Change the end, tag or note of a logged entry, found by its start time
+    #[command(group(clap::ArgGroup::new("change").required(true).multiple(true).args(["set_end", "set_tag", "set_note"])))]
+    Edit {
+        /// Start timestamp of the entry to edit (RFC3339, as shown by `list`)
+        #[arg(long)]
+        start: String,
+        /// New end (RFC3339); the duration is recomputed as end - start
+        #[arg(long)]
+        set_end: Option<String>,
+        #[arg(long)]
+        set_tag: Option<String>,
+        /// New note ("" removes it)
+        #[arg(long)]
+        set_note: Option<String>,
+    },
+    /// Rename a tag across all logged entries and the running session
+    RenameTag { old: String, new: String },
+    /// List logged sessions, newest first
+    List {
+        /// Only this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only sessions starting on/after this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Show at most N sessions
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Print totals and streaks without touching README, assets or git
+    Stats {
+        /// Window for the recent totals
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(i32).range(1..))]
+        days: i32,
+        /// Machine-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// This week/month vs the previous one, per tag (no README side effects)
+    Summary {
+        #[arg(long, default_value = "week", value_parser = ["week", "month", "all"])]
+        period: String,
+        /// Machine-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Time logged today (config `timezone`, default UTC), including running sessions
+    Today,
+    /// Say how old README.md is; exit 4 if its stats no longer match the logs (pre-push hook)
+    CheckFreshness,
+    /// Live view of running sessions and today's totals (q or Ctrl-C to quit)
+    Watch {
+        /// Pause running sessions while the desktop is idle, resume on input (X11, needs `xprintidle`)
+        #[cfg(feature = "idle")]
+        #[arg(long)]
+        auto_pause: bool,
+        /// Idle minutes before --auto-pause kicks in
+        #[cfg(feature = "idle")]
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        idle_minutes: u64,
+    },
+    /// Show the running sessions, if any (exit 3 if none)
+    Status {
+        /// Machine-readable output, e.g. for status bars
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a shell completion script to stdout
+    #[command(after_help = "Install, e.g.:\n  \
+        bash: blazectl completions bash > ~/.local/share/bash-completion/completions/blazectl\n  \
+        zsh:  blazectl completions zsh > ~/.zfunc/_blazectl  (with ~/.zfunc in $fpath)\n  \
+        fish: blazectl completions fish > ~/.config/fish/completions/blazectl.fish\n  \
+        powershell: blazectl completions powershell >> $PROFILE")]
+    Completions {
+        shell: clap_complete::Shell,
+    },
+    /// View or change .blaze/config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigCmd,
+    },
     /// Force README regeneration
-    RenderReadme,
+    RenderReadme {
+        /// SVG color theme (default: config `theme`, else dark)
+        #[arg(long, value_parser = ["light", "dark"])]
+        theme: Option<String>,
+        /// Commit right away, ignoring the auto-commit interval
+        #[arg(long)]
+        force_commit: bool,
+        /// Days shown in the activity chart (default: config `chart_days`, else 75)
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        chart_days: Option<u32>,
+        /// Activity chart image format (default: config `chart_format`, else svg)
+        #[arg(long, value_parser = ["svg", "png"])]
+        format: Option<String>,
+        /// Only entries starting on/after this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only entries starting on/before this date; tables end here
+        #[arg(long)]
+        until: Option<String>,
+        /// Write the markdown here instead of config `readme_path` (`-` for stdout);
+        /// SVGs still go to config `asset_dir`
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<String>,
+        /// Activity chart shows only this tag
+        #[arg(long, value_name = "TAG")]
+        only_tag: Option<String>,
+        /// Throw away the config `index_cache` file and rebuild it from the logs
+        #[arg(long)]
+        rebuild_index: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCmd {
+    /// Print the value in effect for a key (`goals.<tag>_daily_minutes` for goals)
+    Get { key: String },
+    /// Set a key in config.toml; the value is checked like at startup
+    Set { key: String, value: String },
+    /// Print every setting in effect, defaults included
+    List,
+}
+
+/// Log sessions whose `start --for` time is up, before running the command. Only logs:
+/// the notice goes to stderr (stdout may be `export`/`--json` output) and the README is
+/// left to the commands that render. Returns whether anything was logged.
+fn finish_due() -> bool {
+    let done = active::finish_due().unwrap_or_else(|e| {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    });
+    for e in &done {
+        if !util::quiet() {
+            eprintln!("Planned end reached: stopped {} at {} ({}).",
+                e.activity, util::local_iso(&e.end), util::hm(e.duration.whole_seconds()));
+        }
+    }
+    append_all(&done);
+    !done.is_empty()
+}
+
+/// Synchronous: README + daily commit.
+/// Returns only after README.md and the SVGs are written and closed.
+fn render_and_commit() {
+    let render = readme::Palette::resolve(None)
+        .and_then(|pal| readme::render_all(&pal, &readme::RenderOptions::default()))
+        .and_then(|md| readme::write_readme(&md));
+    if let Err(e) = render { util::error(format_args!("readme: {e}")); }
+
+    if let Err(e) = gitops::auto_commit_if_due(false) { util::error(format_args!("git: {e}")); }
+}
+
+/// "train since <start> (<ago>), 0h 20m elapsed[, paused since ...]" for `status`.
+fn session_line(r: &active::Running) -> String {
+    let elapsed = util::hm(r.elapsed.whole_seconds());
+    let tag = util::paint(util::Style::Green, &r.tag);
+    let start = format!("{} ({})", util::local_iso(&r.start), util::ago(&r.start));
+    match &r.paused_since {
+        Some(p) => format!("{tag} since {start}, {elapsed} elapsed, paused since {} ({})", util::local_iso(p), util::ago(p)),
+        None => format!("{tag} since {start}, {elapsed} elapsed"),
+    }
+}
+
+/// Log entries closed by `auto_switch`.
+fn append_all(entries: &[store::Entry]) {
+    for e in entries {
+        if let Err(err) = store::append_entry(e) {
+            eprintln!("append error: {err}");
+            std::process::exit(1);
+        }
+        emit_stop(e);
+    }
+}
+
+/// What `watch` does with sessions it stops at their planned end: what `stop` does.
+fn log_finished(entries: &[store::Entry]) -> anyhow::Result<()> {
+    for e in entries {
+        store::append_entry(e)?;
+        emit_stop(e);
+    }
+    render_and_commit();
+    Ok(())
+}
+
+fn emit_stop(e: &store::Entry) {
+    util::emit_event("stop", &e.activity, &e.end, Some(e.duration.whole_seconds()));
+}
+
+/// Normalize every tag argument up front so bad tags fail in one place.
+fn normalize_tags(cmd: &mut Cmd) -> anyhow::Result<()> {
+    let norm = |t: &mut String| -> anyhow::Result<()> { *t = active::normalize_tag(t)?; Ok(()) };
+    match cmd {
+        Cmd::Start { tag, .. } | Cmd::Stop { tag, .. } | Cmd::Cancel { tag } | Cmd::Pause { tag }
+        | Cmd::Resume { tag } | Cmd::Log { tag, .. } => norm(tag),
+        Cmd::Switch { from, to } => { norm(from)?; norm(to) }
+        Cmd::RenameTag { old, new } => { norm(old)?; norm(new) }
+        Cmd::List { tag: Some(tag), .. } | Cmd::Edit { set_tag: Some(tag), .. }
+        | Cmd::RenderReadme { only_tag: Some(tag), .. } => norm(tag),
+        _ => Ok(()),
+    }
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    store::set_sync(cli.sync || std::env::var("BLAZE_SYNC").is_ok_and(|v| v == "1"));
+    gitops::set_push(cli.push);
+    util::set_verbose(cli.verbose);
+    util::set_dry_run(cli.dry_run);
+    util::set_emit_event(cli.emit_event);
+    util::set_no_color(cli.no_color);
+    util::set_quiet(cli.quiet);
+
+    if let Some(d) = cli.dir.clone().or_else(|| std::env::var_os("BLAZE_HOME").map(Into::into)) {
+        store::set_dir(d);
+    }
+
+    // needs no data dir
+    if let Cmd::Completions { shell } = cli.cmd {
+        clap_complete::generate(shell, &mut Cli::command(), "blazectl", &mut std::io::stdout());
+        return;
+    }
 
     // Ensure .blaze exists
     store::ensure_dirs().expect(".blaze init failed");
 
+    // `config set` must work even when the current file doesn't load
+    if let Cmd::Config { action: ConfigCmd::Set { key, value } } = &cli.cmd {
+        if let Err(e) = config::set_key(key, value) {
+            eprintln!("config error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Err(e) = config::load() {
+        eprintln!("config error: {e}");
+        std::process::exit(1);
+    }
+    if let Err(e) = normalize_tags(&mut cli.cmd) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+    let finished = finish_due();
+
     match cli.cmd {
-        Cmd::Start { tag } => {
-            active::start(&tag).unwrap_or_else(|e| {
+        Cmd::Start { tag, at, for_span, note } => {
+            let for_secs = for_span.map(|f| match util::parse_span_seconds(&f) {
+                Some(secs) if secs > 0 => secs,
+                _ => {
+                    eprintln!("start error: invalid --for {f:?} (e.g. 50m, 1h30m, PT50M)");
+                    std::process::exit(1);
+                }
+            });
+            let started = active::start(&tag, at.as_deref(), for_secs, note).unwrap_or_else(|e| {
                 eprintln!("start error: {e}");
                 std::process::exit(1);
             });
+            if let Some(started) = started {
+                append_all(&started.stopped);
+                util::emit_event("start", &tag, &started.start, None);
+                if finished || !started.stopped.is_empty() { render_and_commit(); }
+            }
         }
-        Cmd::Stop { tag } => {
-            match active::stop(&tag) {
-                Ok(Some(entry)) => {
-                    if let Err(e) = store::append_entry(&entry) {
-                        eprintln!("append error: {e}");
-                        std::process::exit(1);
-                    }
-                    // Synchronous: README + daily commit
-                    // NOTE:
-                    // SVG + README writes are buffered. We do a naive mtime poll before auto-commit.
-                    // This is not perfectly reliable under fs writeback delays.
-                    // Acceptable for personal workflow; revisit if failures become annoying.
-
-                    let before = std::fs::metadata("assets/activity.svg")
-                        .ok()
-                        .and_then(|m| m.modified().ok());
-
-                    if let Err(e) = readme::render_all() { eprintln!("readme: {e}"); }
-
-                    for _ in 0..20 {
-                        let now = std::fs::metadata("assets/activity.svg")
-                            .ok()
-                            .and_then(|m| m.modified().ok());
-
-                        if now != before { break; }
-                        std::thread::sleep(std::time::Duration::from_millis(100));
-                    }
-
-                    if let Err(e) = gitops::auto_commit_if_due() { eprintln!("git: {e}"); }
+        Cmd::Stop { tag, note, force } => {
+            match active::stop(&tag, force) {
+                Ok(Some(mut entry)) => {
+                    if note.is_some() { entry.note = note; }
+                    append_all(std::slice::from_ref(&entry));
+                    render_and_commit();
                 }
                 Ok(None) => {
-                    println!("No active `{tag}` session.");
+                    util::info(format_args!("No active `{tag}` session."));
+                    let others = active::running().unwrap_or_default();
+                    if let [only] = others.as_slice() {
+                        util::info(format_args!("Did you mean `blazectl stop {}`?", only.tag));
+                    } else if !others.is_empty() {
+                        let tags: Vec<&str> = others.iter().map(|r| r.tag.as_str()).collect();
+                        util::info(format_args!("Running: {}.", tags.join(", ")));
+                    }
+                    std::process::exit(EXIT_IDLE);
                 }
                 Err(e) => {
                     eprintln!("stop error: {e}");
@@ -77,17 +404,311 @@ fn main() {
                 }
             }
         }
-        Cmd::Status => {
-            match active::status() {
-                Ok(Some((tag, start))) => println!("Active: {tag} since {start} (UTC)"),
-                Ok(None) => println!("No active session."),
-                Err(e) => { eprintln!("status error: {e}"); std::process::exit(1); }
+        Cmd::Cancel { tag } => {
+            match active::cancel(&tag) {
+                Ok(true) => {
+                    util::info(format_args!("Cancelled active {tag} session"));
+                    util::emit_event("cancel", &tag, &util::iso(util::now_utc()), None);
+                }
+                Ok(false) => util::info(format_args!("No active {tag} session.")),
+                Err(e) => {
+                    eprintln!("cancel error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Cmd::Pause { tag } => {
+            active::pause(&tag).unwrap_or_else(|e| {
+                eprintln!("pause error: {e}");
+                std::process::exit(1);
+            });
+        }
+        Cmd::Resume { tag } => {
+            active::resume(&tag).unwrap_or_else(|e| {
+                eprintln!("resume error: {e}");
+                std::process::exit(1);
+            });
+        }
+        Cmd::Switch { from, to } => {
+            let entry = match active::stop(&from, false) {
+                Ok(Some(entry)) => entry,
+                Ok(None) => {
+                    eprintln!("switch error: no active `{from}` session");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("switch error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            append_all(std::slice::from_ref(&entry));
+            match active::start(&to, None, None, None) {
+                Ok(Some(started)) => {
+                    append_all(&started.stopped);
+                    util::emit_event("start", &to, &started.start, None);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("start error: {e}");
+                    std::process::exit(1);
+                }
+            }
+            render_and_commit();
+        }
+        Cmd::Log { tag, start, end, duration, note } => {
+            let mut entry = store::manual_entry(&tag, &start, end.as_deref(), duration.as_deref())
+                .unwrap_or_else(|e| {
+                    eprintln!("log error: {e}");
+                    std::process::exit(1);
+                });
+            entry.note = note;
+            if let Err(e) = store::append_entry(&entry) {
+                eprintln!("append error: {e}");
+                std::process::exit(1);
+            }
+            util::info(format_args!("Logged {tag}: {} ({} -> {})", util::hm(entry.duration.whole_seconds()), entry.start, entry.end));
+            render_and_commit();
+        }
+        Cmd::List { tag, since, limit } => {
+            let since = since.as_deref().map(util::parse_date).transpose().unwrap_or_else(|e| {
+                eprintln!("list error: {e}");
+                std::process::exit(1);
+            });
+            match readme::list(tag.as_deref(), since, limit) {
+                Ok(out) => println!("{out}"),
+                Err(e) => { eprintln!("list error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Export { format, out } => {
+            let data = store::export(&format).unwrap_or_else(|e| {
+                eprintln!("export error: {e}");
+                std::process::exit(1);
+            });
+            match out {
+                Some(path) => {
+                    if let Err(e) = store::write_file(&path, &data) {
+                        eprintln!("export error: {e}");
+                        std::process::exit(1);
+                    }
+                }
+                None => print!("{data}"),
+            }
+        }
+        Cmd::Import { format: _, path } => {
+            let res = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|text| store::import_csv(&text));
+            match res {
+                Ok((imported, dups, invalid)) => {
+                    util::info(format_args!("Imported {imported}, skipped {dups} duplicate(s), {invalid} invalid row(s)."));
+                    if imported > 0 { render_and_commit(); }
+                }
+                Err(e) => { eprintln!("import error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Doctor { fix } => {
+            match store::doctor(fix) {
+                Ok(issues) if issues.is_empty() => println!("All entries look good."),
+                Ok(issues) => {
+                    for i in &issues { println!("{i}"); }
+                    if fix {
+                        println!("Fixed {} issue(s); originals saved as .bak", issues.len());
+                        render_and_commit();
+                    } else {
+                        println!("{} issue(s) found; run `blazectl doctor --fix` to repair", issues.len());
+                        std::process::exit(1);
+                    }
+                }
+                Err(e) => { eprintln!("doctor error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Undo => {
+            match store::remove_last_entry() {
+                Ok(Some(line)) => {
+                    util::info(format_args!("Removed: {line}"));
+                    render_and_commit();
+                }
+                Ok(None) => util::info(format_args!("No entries to undo.")),
+                Err(e) => { eprintln!("undo error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Reset { yes: false } => {
+            let n = store::track_files().len() + store::dir().join("active.json").exists() as usize;
+            util::info(format_args!("Would archive {n} file(s) into {}/archive/<timestamp>/ and re-render the README; rerun with --yes.",
+                store::dir().display()));
+            std::process::exit(1);
+        }
+        Cmd::Reset { yes: true } => {
+            match store::archive_all() {
+                Ok(Some((dest, n))) => {
+                    util::info(format_args!("Archived {n} file(s) to {}", dest.display()));
+                    render_and_commit();
+                }
+                Ok(None) => util::info(format_args!("Nothing to archive.")),
+                Err(e) => { eprintln!("reset error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Edit { start, set_end, set_tag, set_note } => {
+            let edit = store::EntryEdit { end: set_end, tag: set_tag, note: set_note };
+            match store::edit_entry(&start, &edit) {
+                Ok(line) => {
+                    util::info(format_args!("Updated: {line}"));
+                    render_and_commit();
+                }
+                Err(e) => { eprintln!("edit error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::RenameTag { old, new } => {
+            if old == new {
+                util::info(format_args!("Nothing to do."));
+                return;
+            }
+            let res = active::rename_tag(&old, &new)
+                .and_then(|active| Ok((store::rename_tag(&old, &new)?, active)));
+            match res {
+                Ok((0, false)) => util::info(format_args!("No `{old}` entries or session found.")),
+                Ok((n, active)) => {
+                    util::info(format_args!("Renamed {n} entr{} from {old} to {new}{}", if n == 1 { "y" } else { "ies" },
+                        if active { " (and the running session)" } else { "" }));
+                    render_and_commit();
+                }
+                Err(e) => { eprintln!("rename-tag error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Stats { days, json } => {
+            match readme::stats(days, json) {
+                Ok(out) => println!("{out}"),
+                Err(e) => { eprintln!("stats error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Summary { period, json } => {
+            match readme::summary(&period, json) {
+                Ok(out) => println!("{out}"),
+                Err(e) => { eprintln!("summary error: {e}"); std::process::exit(1); }
             }
         }
-        Cmd::RenderReadme => {
-            if let Err(e) = readme::render_all() {
+        Cmd::CheckFreshness => {
+            match readme::freshness() {
+                Ok(f) => {
+                    util::info(format_args!("README updated {} ({}).", util::ago(&util::iso(f.updated)), util::iso(f.updated)));
+                    if let Some(why) = f.stale {
+                        println!("Stale: {why}; run `blazectl render-readme`.");
+                        std::process::exit(EXIT_STALE);
+                    }
+                }
+                Err(e) => { eprintln!("freshness error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::Today => {
+            // only the part of a running session that falls on today counts
+            match active::live_today().and_then(|live| readme::today(&live)) {
+                Ok(out) => println!("{out}"),
+                Err(e) => { eprintln!("today error: {e}"); std::process::exit(1); }
+            }
+        }
+        #[cfg(feature = "idle")]
+        Cmd::Watch { auto_pause, idle_minutes } => {
+            if let Err(e) = watch::run(&log_finished, auto_pause.then_some(idle_minutes)) {
+                eprintln!("watch error: {e}");
+                std::process::exit(1);
+            }
+        }
+        #[cfg(not(feature = "idle"))]
+        Cmd::Watch {} => {
+            if let Err(e) = watch::run(&log_finished) { eprintln!("watch error: {e}"); std::process::exit(1); }
+        }
+        Cmd::Status { json: true } => {
+            let running = active::status().unwrap_or_else(|e| {
+                eprintln!("status error: {e}");
+                std::process::exit(1);
+            });
+            let session = |r: &active::Running| serde_json::json!({
+                "tag": r.tag,
+                "start": r.start,
+                "elapsed_seconds": r.elapsed.whole_seconds(),
+                "paused": r.paused_since.is_some(),
+                "planned_end": r.planned_end,
+                "note": r.note,
+            });
+            // the oldest session's fields stay at the top level for single-session consumers
+            let Some(oldest) = running.first() else {
+                println!("{}", serde_json::json!({ "active": false }));
+                std::process::exit(EXIT_IDLE);
+            };
+            let mut v = session(oldest);
+            v["active"] = true.into();
+            v["sessions"] = running.iter().map(session).collect();
+            println!("{v}");
+        }
+        Cmd::Status { json: false } => {
+            let running = active::status().unwrap_or_else(|e| {
+                eprintln!("status error: {e}");
+                std::process::exit(1);
+            });
+            match running.as_slice() {
+                [] => {
+                    println!("No active session.");
+                    std::process::exit(EXIT_IDLE);
+                }
+                [r] => {
+                    println!("Active: {}", session_line(r));
+                    if let Some(end) = &r.planned_end {
+                        println!("Stops at {} (start --for)", util::local_iso(end));
+                    }
+                    if let Some(note) = &r.note { println!("Note: {note}"); }
+                }
+                all => {
+                    println!("Active sessions ({}):", all.len());
+                    for r in all {
+                        println!("  {}", session_line(r));
+                        if let Some(end) = &r.planned_end {
+                            println!("    stops at {} (start --for)", util::local_iso(end));
+                        }
+                        if let Some(note) = &r.note { println!("    note: {note}"); }
+                    }
+                }
+            }
+        }
+        Cmd::Completions { .. } => unreachable!("handled before .blaze init"),
+        Cmd::Config { action } => {
+            let out = match action {
+                ConfigCmd::Get { key } => config::get_key(&key),
+                ConfigCmd::List => config::list(),
+                ConfigCmd::Set { .. } => unreachable!("handled before config load"),
+            };
+            match out {
+                Ok(out) => println!("{out}"),
+                Err(e) => { eprintln!("config error: {e}"); std::process::exit(1); }
+            }
+        }
+        Cmd::RenderReadme { theme, force_commit, chart_days, format, since, until, output, only_tag, rebuild_index } => {
+            if rebuild_index {
+                if let Err(e) = index::remove() { eprintln!("readme: {e}"); std::process::exit(1); }
+            }
+            let pal = readme::Palette::resolve(theme.as_deref()).unwrap_or_else(|e| {
+                eprintln!("readme: {e}");
+                std::process::exit(1);
+            });
+            let date = |s: Option<String>| s.as_deref().map(util::parse_date).transpose().unwrap_or_else(|e| {
                 eprintln!("readme: {e}");
                 std::process::exit(1);
+            });
+            let opts = readme::RenderOptions { chart_days, format, since: date(since), until: date(until), only_tag };
+            if opts.since.zip(opts.until).is_some_and(|(s, u)| s > u) {
+                eprintln!("readme: --since is after --until");
+                std::process::exit(1);
+            }
+            let written = readme::render_all(&pal, &opts).and_then(|md| match output.as_deref() {
+                None => readme::write_readme(&md),
+                Some("-") => { print!("{md}"); Ok(()) }
+                Some(path) => store::write_file(std::path::Path::new(path), &md),
+            });
+            if let Err(e) = written {
+                eprintln!("readme: {e}");
+                std::process::exit(1);
+            }
+            if force_commit {
+                if let Err(e) = gitops::auto_commit_if_due(true) { eprintln!("git: {e}"); }
             }
         }
     }
@@ -1,128 +1,752 @@
-use std::fs::File;
+use std::path::Path;
 use anyhow::Result;
-use std::{collections::HashMap, fs};
-use time::{Duration, OffsetDateTime, Date, format_description::well_known::Rfc3339};
+use std::collections::HashMap;
+use time::{Duration, OffsetDateTime, Date};
 
-use crate::util::{now_utc, iso};
+use crate::config;
+use crate::util::{now_utc, iso, hm, hms};
 
 use plotters::prelude::*; // SVG renderer
 use plotters::element::PathElement;
 
-#[derive(Default, Clone, Copy)]
-pub(crate) struct Totals { train: i64, battle: i64 }
+/// Seconds per tag; tags are whatever appears in the data. Session counts are only
+/// kept where whole entries are added (`add_session`), not for per-day splits.
+#[derive(Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Totals {
+    by_tag: HashMap<String, i64>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    sessions: HashMap<String, i64>,
+}
 impl Totals {
     fn add(&mut self, tag: &str, secs: i64) {
-        match tag {
-            "train" => self.train += secs,
-            "battle" => self.battle += secs,
-            _ => {}
-        }
+        if tag.is_empty() { return; }
+        *self.by_tag.entry(tag.to_string()).or_default() += secs;
+    }
+    /// `add` one whole logged entry and count it.
+    fn add_session(&mut self, tag: &str, secs: i64) {
+        if tag.is_empty() { return; }
+        self.add(tag, secs);
+        *self.sessions.entry(tag.to_string()).or_default() += 1;
+    }
+    pub(crate) fn merge(&mut self, other: &Totals) {
+        for (tag, secs) in &other.by_tag { self.add(tag, *secs); }
+        for (tag, n) in &other.sessions { *self.sessions.entry(tag.clone()).or_default() += n; }
+    }
+    pub fn get(&self, tag: &str) -> i64 { self.by_tag.get(tag).copied().unwrap_or(0) }
+    pub fn total(&self) -> i64 { self.by_tag.values().sum() }
+    pub fn session_count(&self, tag: &str) -> i64 { self.sessions.get(tag).copied().unwrap_or(0) }
+    pub fn session_total(&self) -> i64 { self.sessions.values().sum() }
+    /// Tags ordered by time spent (most first), then name.
+    pub fn tags(&self) -> Vec<String> {
+        let mut v: Vec<(&String, &i64)> = self.by_tag.iter().collect();
+        v.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        v.into_iter().map(|(t, _)| t.clone()).collect()
     }
-    fn total(&self) -> i64 { self.train + self.battle }
 }
 
-pub fn render_all() -> Result<()> {
-    let now = now_utc();
+/// Chart colors shared by the SVG renderers.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub bg: RGBColor,
+    pub text: RGBColor,
+    /// main graph line/points and heatmap cells
+    pub accent: RGBColor,
+    /// second series in per-tag charts
+    pub accent2: RGBColor,
+    pub border: RGBColor,
+    pub trend: RGBColor,
+    /// running-total line (`show_cumulative`)
+    pub cumulative: RGBColor,
+    /// outline of empty heatmap cells
+    pub empty: RGBColor,
+}
 
-    let today = now.date();
-    let last7_dates = days_back(today, 7);
-    let last30_dates = days_back(today, 30);
-    let last75_dates = days_back(today, 75);
+impl Palette {
+    pub fn dark() -> Self {
+        Palette {
+            bg: RGBColor(19, 23, 31),          // rgb(19, 22.5, 30.5) -> rounded
+            text: RGBColor(194, 199, 208),     // #c2c7d0
+            accent: RGBColor(1, 170, 255),     // #01aaff
+            accent2: RGBColor(255, 170, 1),    // #ffaa01
+            border: RGBColor(88, 186, 236),
+            trend: RGBColor(210, 20, 20),      // keep the red trend
+            cumulative: RGBColor(188, 140, 255), // #bc8cff
+            empty: RGBColor(40, 46, 58),
+        }
+    }
 
-    let entries = read_all_entries()?;
+    pub fn light() -> Self {
+        Palette {
+            bg: RGBColor(255, 255, 255),
+            text: RGBColor(36, 41, 47),        // #24292f
+            accent: RGBColor(9, 105, 218),     // #0969da
+            accent2: RGBColor(191, 135, 0),    // #bf8700
+            border: RGBColor(208, 215, 222),
+            trend: RGBColor(207, 34, 46),
+            cumulative: RGBColor(130, 80, 223),
+            empty: RGBColor(235, 237, 240),
+        }
+    }
 
-    let mut all_time = Totals::default();
-    let mut per_day: HashMap<Date, Totals> = HashMap::new();
+    /// `light` | `dark`
+    pub fn named(theme: &str) -> Result<Self> {
+        match theme {
+            "dark" => Ok(Self::dark()),
+            "light" => Ok(Self::light()),
+            _ => Err(anyhow::anyhow!("unknown theme: {theme} (use light|dark)")),
+        }
+    }
 
-    for v in entries {
-        let activity = v.get("activity").and_then(|x| x.as_str()).unwrap_or("");
-        let start_iso = v.get("start").and_then(|x| x.as_str()).unwrap_or("");
-        let dur_secs = parse_duration_seconds(v.get("duration").and_then(|x| x.as_str()).unwrap_or("PT0S"));
+    /// Theme from the flag (or config), with config color overrides applied.
+    pub fn resolve(theme: Option<&str>) -> Result<Self> {
+        let cfg = config::get();
+        let mut pal = Self::named(theme.unwrap_or(&cfg.theme))?;
+        let hex = |c: &Option<String>| -> Result<Option<RGBColor>> {
+            Ok(match c {
+                Some(c) => { let (r, g, b) = config::parse_hex(c)?; Some(RGBColor(r, g, b)) }
+                None => None,
+            })
+        };
+        if let Some(c) = hex(&cfg.accent_color)? { pal.accent = c; }
+        if let Some(c) = hex(&cfg.trend_color)? { pal.trend = c; }
+        if let Some(c) = hex(&cfg.bg_color)? { pal.bg = c; }
+        Ok(pal)
+    }
 
-        all_time.add(activity, dur_secs);
+    /// Color for `tag` as the i-th per-tag series: its `[tags.<tag>] color`, else `series(i)`.
+    pub fn tag_color(&self, tag: &str, i: usize) -> RGBColor {
+        config::get().tag_style(tag)
+            .and_then(|t| t.color.as_deref())
+            .and_then(|c| config::parse_hex(c).ok())
+            .map_or_else(|| self.series(i), |(r, g, b)| RGBColor(r, g, b))
+    }
 
-        if let Ok(st_dt) = OffsetDateTime::parse(start_iso, &Rfc3339).map(|t| t.date()) {
-            per_day.entry(st_dt).or_default().add(activity, dur_secs);
+    /// Color of the i-th per-tag series: accent, accent2, then fixed extras.
+    pub fn series(&self, i: usize) -> RGBColor {
+        const EXTRA: [RGBColor; 3] = [RGBColor(63, 185, 80), RGBColor(163, 113, 247), RGBColor(219, 97, 162)];
+        match i {
+            0 => self.accent,
+            1 => self.accent2,
+            _ => EXTRA[(i - 2) % EXTRA.len()],
         }
     }
+}
+
+/// Images under config `asset_dir` that the README embeds; auto-commit stages them with it.
+pub(crate) const ASSETS: [&str; 5] = ["activity.svg", "activity.png", "heatmap.svg", "hours.svg", "summary.svg"];
+
+/// Per-run `render_all` settings (`render-readme` flags); the default is config + all-time.
+#[derive(Default)]
+pub struct RenderOptions {
+    /// overrides config `chart_days` for the activity SVG window
+    pub chart_days: Option<u32>,
+    /// `svg` | `png`; overrides config `chart_format`
+    pub format: Option<String>,
+    /// only entries starting on/after this local date
+    pub since: Option<Date>,
+    /// only entries starting on/before this local date; also anchors "today"
+    pub until: Option<Date>,
+    /// plot just this tag in the activity chart
+    pub only_tag: Option<String>,
+}
+
+/// What the activity chart plots.
+#[derive(Clone, Copy)]
+pub(crate) enum ActivitySeries<'a> {
+    /// all tags summed, plus the trend line
+    Total,
+    /// one line per tag with a legend (config `chart_mode = "per_tag"`)
+    PerTag,
+    /// a single tag, plus its trend line
+    Only(&'a str),
+}
+
+/// Where rendered files go: the repo root, or under `--dry-run` a fresh scratch dir,
+/// one per invocation so concurrent previews don't clobber each other (kept afterwards
+/// for inspection).
+fn out_root() -> Result<std::path::PathBuf> {
+    static DRY_ROOT: std::sync::OnceLock<std::path::PathBuf> = std::sync::OnceLock::new();
+    if !crate::util::dry_run() { return Ok(crate::store::root()); }
+    if let Some(p) = DRY_ROOT.get() { return Ok(p.clone()); }
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_nanos();
+    let p = std::env::temp_dir().join(format!("blazectl-dry-run-{}-{nanos}", std::process::id()));
+    // create_dir, not create_dir_all: fails rather than reuse an existing dir
+    std::fs::create_dir(&p)?;
+    Ok(DRY_ROOT.get_or_init(|| p).clone())
+}
+
+/// Draw one chart into `path`; the renderers drop their backend before returning, so
+/// the file is complete and can be synced per `--sync`.
+fn render_asset(path: &Path, render: impl FnOnce(&Path) -> Result<()>) -> Result<()> {
+    render(path)?;
+    crate::store::sync_file(path)
+}
+
+/// Render the SVGs into config `asset_dir` and return the README markdown (hand-written
+/// text around the markers kept); `write_readme` puts it in place.
+pub fn render_all(pal: &Palette, opts: &RenderOptions) -> Result<String> {
+    let now = now_utc();
+    let cfg = config::get();
+    let chart_days = opts.chart_days.unwrap_or(cfg.chart_days);
+
+    // a past --until reports as of that day, so the daily/weekly tables cover the range
+    let today = opts.until.map_or(crate::util::today(), |u| u.min(crate::util::today()));
+    let last7_dates = days_back(today, 7);
+    let last30_dates = days_back(today, 30);
+    let chart_dates = days_back(today, chart_days as i32);
+    let last365_dates = days_back(today, 365);
+
+    let (all_time, per_day, hours) = if opts.since.is_none() && opts.until.is_none() {
+        crate::index::aggregate()?
+    } else {
+        let mut entries = crate::store::read_all_entries()?;
+        entries.retain(|e| {
+            let d = crate::util::local(e.start).date();
+            opts.since.is_none_or(|s| d >= s) && opts.until.is_none_or(|u| d <= u)
+        });
+        let (all_time, per_day) = aggregate_entries(&entries);
+        (all_time, per_day, hour_totals(&entries))
+    };
 
     let last7_tot = sum_over(&per_day, &last7_dates);
     let last30_tot = sum_over(&per_day, &last30_dates);
 
-    let mut last30_tag = Totals::default();
-    for d in &last30_dates {
-        if let Some(t) = per_day.get(d) {
-            last30_tag.train += t.train;
-            last30_tag.battle += t.battle;
-        }
-    }
+    let last30_tag = sum_over(&per_day, &last30_dates);
 
     let mut last7_rows = last7_dates.clone();
     last7_rows.sort();
     let daily7: Vec<(Date, Totals)> = last7_rows
         .into_iter()
-        .map(|d| (d, per_day.get(&d).copied().unwrap_or_default()))
+        .map(|d| (d, per_day.get(&d).cloned().unwrap_or_default()))
         .collect();
 
-    let streak_any = streak_days(&per_day, today, |t| t.total() > 0);
-    let streak_train = streak_days(&per_day, today, |t| t.train > 0);
-    let streak_battle = streak_days(&per_day, today, |t| t.battle > 0);
+    let streaks = streak_rows(&per_day, today, &all_time);
 
     // keep ASCII generator available (unused in README but handy)
-    let ascii_area = ascii_area_30d(&per_day, &last75_dates, 12);
+    let ascii_area = ascii_area_30d(&per_day, &chart_dates, 12);
 
     // generate SVG asset (scales nicely on mobile/GitHub)
-    let _ = std::fs::create_dir_all("assets")?;
-    render_activity_svg(&per_day, &last75_dates, "assets/activity.svg", 900, 240)?;
+    // --dry-run renders next to a scratch copy and reports what would change
+    let root = out_root()?;
+    let assets = root.join(&cfg.asset_dir);
+    std::fs::create_dir_all(&assets)?;
+    let series = match (&opts.only_tag, cfg.chart_mode.as_str()) {
+        (Some(tag), _) => ActivitySeries::Only(tag),
+        (None, "per_tag") => ActivitySeries::PerTag,
+        _ => ActivitySeries::Total,
+    };
+    let chart_file = if opts.format.as_deref().unwrap_or(&cfg.chart_format) == "png" { "activity.png" } else { "activity.svg" };
+    // an empty-state README (see `render_md`) embeds no charts
+    if all_time.session_total() > 0 || all_time.total() > 0 {
+        render_asset(&assets.join(chart_file), |p| render_activity(&per_day, &chart_dates, p, (cfg.chart_width, cfg.chart_height), pal, series))?;
+        render_asset(&assets.join("heatmap.svg"), |p| render_heatmap_svg(&per_day, &last365_dates, p, 12, pal))?;
+        render_asset(&assets.join("hours.svg"), |p| render_hours_svg(&hours, p, (cfg.chart_width, cfg.chart_height), pal))?;
+        render_asset(&assets.join("summary.svg"), |p| render_summary_card_svg(&CardStats::year(&per_day, today), p, pal))?;
+        crate::util::trace(format_args!("rendered activity/heatmap/hours/summary SVGs in {}", assets.display()));
+    } else {
+        // charts from before a reset would otherwise be committed next to the empty README
+        for a in ASSETS {
+            match std::fs::remove_file(assets.join(a)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+    }
 
+    let range = match (opts.since, opts.until) {
+        (None, None) => None,
+        (since, until) => Some(format!(
+            "{} to {}",
+            since.map_or("first entry".to_string(), |d| d.to_string()),
+            until.map_or("today".to_string(), |d| d.to_string()),
+        )),
+    };
+    let best: Vec<(String, (Date, i64))> = std::iter::once("total".to_string())
+        .chain(all_time.tags())
+        .filter_map(|tag| {
+            let secs = |t: &Totals| if tag == "total" { t.total() } else { t.get(&tag) };
+            best_day(&per_day, secs).map(|b| (tag.clone(), b))
+        })
+        .collect();
+    let avg_active = (
+        avg_per_active_day(&per_day, Some(&last30_dates)),
+        avg_per_active_day(&per_day, None),
+    );
     let out = render_md(
         now,
+        range.as_deref(),
+        &best,
+        avg_active,
         all_time,
         &last7_tot,
         &last30_tot,
         &last30_tag,
         &daily7,
-        streak_any,
-        streak_train,
-        streak_battle,
+        &per_day.get(&today).cloned().unwrap_or_default(),
+        &sum_over(&per_day, &weeks_back(today, 1)[0].1),
+        &period_rows(&per_day, &weeks_back(today, 9)),
+        &period_rows(&per_day, &months_back(today, 13)),
+        &streaks,
+        chart_days,
+        opts.only_tag.as_deref(),
+        &asset_href(&cfg.readme_path, &cfg.asset_dir, chart_file),
+        &asset_href(&cfg.readme_path, &cfg.asset_dir, "heatmap.svg"),
+        &asset_href(&cfg.readme_path, &cfg.asset_dir, "hours.svg"),
+        &asset_href(&cfg.readme_path, &cfg.asset_dir, "summary.svg"),
+        &hours,
         &ascii_area, // still passed for compatibility
     )?;
 
-    fs::write("README.md", out)?;
+    // keep hand-written text around the markers, if the README has them
+    let existing = std::fs::read_to_string(crate::store::root().join(&cfg.readme_path)).unwrap_or_default();
+    Ok(splice_markers(&existing, &out).unwrap_or(out))
+}
+
+/// Write `render_all`'s markdown to config `readme_path`; under `--dry-run`, next to the
+/// scratch assets instead, reporting what would change.
+pub fn write_readme(markdown: &str) -> Result<()> {
+    let cfg = config::get();
+    let (root, real_root) = (out_root()?, crate::store::root());
+    let readme = root.join(&cfg.readme_path);
+    if let Some(dir) = readme.parent() { std::fs::create_dir_all(dir)?; }
+    crate::store::write_file(&readme, markdown)?;
+    if crate::util::dry_run() {
+        crate::util::say(format_args!("dry run: rendered into {}", root.display()));
+        let files = std::iter::once(cfg.readme_path.clone())
+            .chain(ASSETS.iter().map(|a| format!("{}/{a}", cfg.asset_dir)));
+        for f in files.filter(|f| root.join(f).exists()) {
+            crate::util::say(format_args!("  {f}: {}", diff_summary(&real_root.join(&f), &root.join(&f))));
+        }
+    }
     Ok(())
 }
 
+/// The day with the most `secs` (most recent on ties); `None` if nothing was logged.
+fn best_day(per_day: &HashMap<Date, Totals>, secs: impl Fn(&Totals) -> i64) -> Option<(Date, i64)> {
+    per_day
+        .iter()
+        .map(|(d, t)| (*d, secs(t)))
+        .filter(|(_, s)| *s > 0)
+        .max_by_key(|(d, s)| (*s, *d))
+}
+
+/// Mean seconds per day that had any activity, over `days` (every day if `None`);
+/// `None` when there were no active days.
+fn avg_per_active_day(per_day: &HashMap<Date, Totals>, days: Option<&[Date]>) -> Option<i64> {
+    let active: Vec<i64> = match days {
+        Some(ds) => ds.iter().filter_map(|d| per_day.get(d)).map(Totals::total).filter(|t| *t > 0).collect(),
+        None => per_day.values().map(Totals::total).filter(|t| *t > 0).collect(),
+    };
+    if active.is_empty() { return None; }
+    Some(active.iter().sum::<i64>() / active.len() as i64)
+}
+
+/// Link from the README to `<asset_dir>/<file>`, both relative to the repo root
+/// (`docs/STATS.md` + `assets` -> `../assets/<file>`).
+fn asset_href(readme_path: &str, asset_dir: &str, file: &str) -> String {
+    let from: Vec<_> = Path::new(readme_path).parent().into_iter().flat_map(|p| p.components()).collect();
+    let to: Vec<_> = Path::new(asset_dir).components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".into(); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()));
+    parts.push(file.to_string());
+    parts.join("/")
+}
+
+const MARK_START: &str = "<!-- blazectl:start -->";
+const MARK_END: &str = "<!-- blazectl:end -->";
+
+/// `existing` with everything between its blazectl markers replaced by `generated`;
+/// `None` if the markers are missing or out of order.
+fn splice_markers(existing: &str, generated: &str) -> Option<String> {
+    let a = existing.find(MARK_START)? + MARK_START.len();
+    let b = a + existing[a..].find(MARK_END)?;
+    Some(format!("{}\n{}\n{}", &existing[..a], generated.trim_end(), &existing[b..]))
+}
+
+/// "new", "unchanged", "lines reordered" or "+A -R lines" between `old` (may be missing) and `new`.
+fn diff_summary(old: &Path, new: &Path) -> String {
+    let Ok(new) = std::fs::read(new) else { return "not rendered".into(); };
+    let Ok(old) = std::fs::read(old) else { return "new".into(); };
+    if old == new { return "unchanged".into(); }
+    let (Ok(old), Ok(new)) = (std::str::from_utf8(&old), std::str::from_utf8(&new)) else {
+        return "changed (binary)".into();
+    };
+    // multiset line diff: good enough to size the change
+    let mut counts: HashMap<&str, i64> = HashMap::new();
+    for l in old.lines() { *counts.entry(l).or_default() -= 1; }
+    for l in new.lines() { *counts.entry(l).or_default() += 1; }
+    let added: i64 = counts.values().filter(|c| **c > 0).sum();
+    let removed: i64 = -counts.values().filter(|c| **c < 0).sum::<i64>();
+    if added == 0 && removed == 0 { return "lines reordered".into(); }
+    format!("+{added} -{removed} lines")
+}
+
+/// (total seconds today, current any-activity streak) for commit messages.
+pub(crate) fn today_and_streak() -> Result<(i64, i32)> {
+    let (_, per_day) = aggregate()?;
+    let today = crate::util::today();
+    let total = per_day.get(&today).map(|t| t.total()).unwrap_or(0);
+    Ok((total, streak_days(&per_day, today, |t| t.total() > 0)))
+}
+
+/// Plain-text (or JSON) summary for `blazectl stats`; touches no files.
+pub fn stats(days: i32, json: bool) -> Result<String> {
+    use std::fmt::Write;
+    let today = crate::util::today();
+    let (all_time, per_day) = aggregate()?;
+    let recent = sum_over(&per_day, &days_back(today, days));
+    let streaks = streak_rows(&per_day, today, &all_time);
+
+    if json {
+        let streaks: serde_json::Map<String, serde_json::Value> = streaks
+            .iter()
+            .map(|(k, cur, long)| (k.clone(), serde_json::json!({ "current": cur, "longest": long })))
+            .collect();
+        let v = serde_json::json!({
+            "all_time": { "total_seconds": all_time.total(), "by_tag": all_time.by_tag },
+            "days": days,
+            "recent": { "total_seconds": recent.total(), "by_tag": recent.by_tag },
+            "streaks": streaks,
+        });
+        return Ok(serde_json::to_string_pretty(&v)?);
+    }
+
+    let mut s = String::new();
+    writeln!(s, "All-time: {}", hm(all_time.total()))?;
+    for tag in all_time.tags() {
+        writeln!(s, "  {tag:<10} {}", hm(all_time.get(&tag)))?;
+    }
+    writeln!(s, "Last {days}d: {}", hms(recent.total()))?;
+    for tag in all_time.tags() {
+        writeln!(s, "  {tag:<10} {}", hms(recent.get(&tag)))?;
+    }
+    writeln!(s, "Streaks (current / longest):")?;
+    for (label, cur, long) in &streaks {
+        writeln!(s, "  {label:<10} {cur}d / {long}d")?;
+    }
+    Ok(s.trim_end().to_string())
+}
+
+/// `blazectl summary`: the current week/month (so far) per tag against the previous one,
+/// or all-time totals for `all`. Changes are percentages; `None` when the previous
+/// period had nothing.
+pub fn summary(period: &str, json: bool) -> Result<String> {
+    use std::fmt::Write;
+    let today = crate::util::today();
+    let (all_time, per_day) = aggregate()?;
+    let periods = match period {
+        "week" => weeks_back(today, 2),
+        "month" => months_back(today, 2),
+        "all" => Vec::new(),
+        _ => return Err(anyhow::anyhow!("unknown period: {period} (use week|month|all)")),
+    };
+    let ((label, cur), prev) = match periods.as_slice() {
+        [(pl, pd), (cl, cd)] => ((cl.clone(), sum_over(&per_day, cd)), Some((pl.clone(), sum_over(&per_day, pd)))),
+        _ => (("all time".to_string(), all_time), None),
+    };
+    let mut tags = cur.tags();
+    if let Some((_, p)) = &prev {
+        for t in p.tags() {
+            if !tags.contains(&t) { tags.push(t); }
+        }
+    }
+    let change = |now: i64, before: i64| (before > 0).then(|| (now - before) as f64 * 100.0 / before as f64);
+    let prev_secs = |tag: Option<&str>| prev.as_ref().map(|(_, p)| tag.map_or(p.total(), |t| p.get(t)));
+
+    if json {
+        let side = |label: &str, t: &Totals| serde_json::json!({
+            "label": label, "total_seconds": t.total(), "by_tag": t.by_tag,
+        });
+        let mut pct = serde_json::Map::new();
+        if prev.is_some() {
+            pct.insert("total".into(), change(cur.total(), prev_secs(None).unwrap_or(0)).into());
+            for tag in &tags {
+                pct.insert(tag.clone(), change(cur.get(tag), prev_secs(Some(tag)).unwrap_or(0)).into());
+            }
+        }
+        let v = serde_json::json!({
+            "period": period,
+            "current": side(&label, &cur),
+            "previous": prev.as_ref().map(|(l, t)| side(l, t)),
+            "change_pct": prev.as_ref().map(|_| pct),
+        });
+        return Ok(serde_json::to_string_pretty(&v)?);
+    }
+
+    let mut s = String::new();
+    match &prev {
+        Some((pl, _)) => writeln!(s, "{label} (so far) vs {pl}:")?,
+        None => writeln!(s, "All time:")?,
+    }
+    let row = |s: &mut String, name: &str, now: i64, before: Option<i64>| -> std::fmt::Result {
+        match before {
+            None => writeln!(s, "  {name:<10} {:>9}", hm(now)),
+            Some(b) => {
+                let pct = change(now, b).map_or("new".to_string(), |p| format!("{p:+.0}%"));
+                writeln!(s, "  {name:<10} {:>9}  vs {:>9}  {pct}", hm(now), hm(b))
+            }
+        }
+    };
+    for tag in &tags {
+        row(&mut s, tag, cur.get(tag), prev_secs(Some(tag)))?;
+    }
+    row(&mut s, "total", cur.total(), prev_secs(None))?;
+    Ok(s.trim_end().to_string())
+}
+
+/// What `check-freshness` found in the rendered README.
+pub struct Freshness {
+    /// its "Updated (UTC)" stamp
+    pub updated: OffsetDateTime,
+    /// why its stats no longer match the logs, if they don't
+    pub stale: Option<String>,
+}
+
+/// Compare the README's "Updated (UTC)" stamp and all-time total against the logs, so a
+/// `log`/`import`/`edit` since the last render shows up (e.g. in a pre-push hook).
+pub fn freshness() -> Result<Freshness> {
+    let path = crate::store::root().join(&config::get().readme_path);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("{}: {e} (run render-readme)", path.display()))?;
+    let field = |name: &str| {
+        let prefix = format!("- **{name}:** ");
+        text.lines().find_map(|l| l.strip_prefix(prefix.as_str())).map(str::trim)
+    };
+    let updated = field("Updated (UTC)")
+        .ok_or_else(|| anyhow::anyhow!("{} has no \"Updated (UTC)\" line (run render-readme)", path.display()))?;
+    let updated = crate::util::parse_iso(updated)?;
+
+    let entries = crate::store::read_all_entries()?;
+    let late = entries.iter().filter_map(|e| e.end.filter(|t| *t > updated).map(|t| (t, e))).max_by_key(|(t, _)| *t);
+    let stale = if let Some((end, e)) = late {
+        Some(format!("a {} session ended at {}, after the last render", e.activity, iso(end)))
+    } else if field("Range").is_none() {
+        // a --since/--until README only covers part of the logs; nothing to compare
+        let want = hm(aggregate_entries(&entries).0.total());
+        match field("All-time (Total)") {
+            Some(shown) if shown == want => None,
+            None if entries.is_empty() => None, // the empty-state README
+            shown => Some(format!("README shows {} all-time, the logs add up to {want}", shown.unwrap_or("no total"))),
+        }
+    } else {
+        None
+    };
+    Ok(Freshness { updated, stale })
+}
+
+/// Today's (local, config `timezone`) per-tag time for `blazectl today`: logged time on today's
+/// date (midnight-split like the daily table) plus `live` (tag, seconds) of running sessions.
+pub fn today(live: &[(String, i64)]) -> Result<String> {
+    use std::fmt::Write;
+    let today = crate::util::today();
+    let (_, per_day) = aggregate()?;
+    let logged = per_day.get(&today).cloned().unwrap_or_default();
+    let mut running = Totals::default();
+    for (tag, secs) in live { running.add(tag, *secs); }
+    let mut all = logged.clone();
+    all.merge(&running);
+
+    let mut s = String::new();
+    writeln!(s, "Today ({today} {}):", crate::util::zone_label())?;
+    for tag in all.tags() {
+        if running.get(&tag) > 0 {
+            writeln!(s, "  {tag:<10} {} (incl. {} running)", hm(all.get(&tag)), hm(running.get(&tag)))?;
+        } else {
+            writeln!(s, "  {tag:<10} {}", hm(all.get(&tag)))?;
+        }
+    }
+    writeln!(s, "  {:<10} {}", "total", hm(all.total()))?;
+    Ok(s.trim_end().to_string())
+}
+
+/// Table of raw sessions for `blazectl list`, newest first.
+pub fn list(tag: Option<&str>, since: Option<Date>, limit: Option<usize>) -> Result<String> {
+    use std::fmt::Write;
+    use crate::util::{ago, short_ts};
+    let mut entries = crate::store::read_all_entries()?;
+    entries.retain(|e| {
+        tag.is_none_or(|t| e.activity == t)
+            && since.is_none_or(|d| crate::util::local(e.start).date() >= d)
+    });
+    entries.sort_by_key(|e| std::cmp::Reverse(e.start));
+    entries.truncate(limit.unwrap_or(usize::MAX));
+
+    let mut s = String::new();
+    let header = format!("{:<19}  {:<19}  {:>11}  {:>9}  {:<10} NOTE", "START", "END", "ENDED", "DURATION", "TAG");
+    writeln!(s, "{}", crate::util::paint(crate::util::Style::Bold, header))?;
+    for e in &entries {
+        let end = e.end.map(iso).unwrap_or_default();
+        let line = format!(
+            "{:<19}  {:<19}  {:>11}  {:>9}  {:<10} {}",
+            short_ts(&iso(e.start)), short_ts(&end), ago(&end), hm(e.duration_seconds), e.activity,
+            e.note.as_deref().unwrap_or(""),
+        );
+        writeln!(s, "{}", line.trim_end())?;
+    }
+    Ok(s.trim_end().to_string())
+}
+
 /* ---------- Helpers ---------- */
 
-fn read_all_entries() -> Result<Vec<serde_json::Value>> {
-    let mut entries = Vec::new();
-    if let Ok(rd) = fs::read_dir(".blaze") {
-        for e in rd.flatten() {
-            let name = e.file_name().to_string_lossy().into_owned();
-            if !(name.starts_with("track-") && name.ends_with(".jsonl")) { continue; }
-            if let Ok(s) = fs::read_to_string(e.path()) {
-                for line in s.lines().filter(|l| !l.trim().is_empty()) {
-                    if let Ok(v) = serde_json::from_str::<serde_json::Value>(line) {
-                        entries.push(v);
-                    }
+/// All-time totals and per-day totals (by start date) over every logged entry.
+fn aggregate() -> Result<(Totals, HashMap<Date, Totals>)> {
+    let (all_time, per_day, _) = crate::index::aggregate()?;
+    Ok((all_time, per_day))
+}
+
+/// `aggregate` over a given set of entries.
+pub(crate) fn aggregate_entries(entries: &[crate::store::LoggedEntry]) -> (Totals, HashMap<Date, Totals>) {
+    let mut all_time = Totals::default();
+    let mut per_day: HashMap<Date, Totals> = HashMap::new();
+
+    for e in entries {
+        all_time.add_session(&e.activity, e.duration_seconds);
+        match e.end {
+            Some(en) => {
+                for (d, secs) in split_by_day(e.start, en, e.duration_seconds) {
+                    per_day.entry(d).or_default().add(&e.activity, secs);
                 }
             }
+            None => per_day.entry(crate::util::local(e.start).date()).or_default().add(&e.activity, e.duration_seconds),
+        }
+    }
+
+    (all_time, per_day)
+}
+
+/// Seconds logged per local hour of day (0..24) over `entries`, each entry spread
+/// over the hours its start..end covers like `split_by_day`.
+pub(crate) fn hour_totals(entries: &[crate::store::LoggedEntry]) -> [i64; 24] {
+    let mut hours = [0i64; 24];
+    for e in entries {
+        for (h, part) in split_by_hour(e.start, e.end.unwrap_or(e.start), e.duration_seconds) {
+            hours[h] += part;
         }
     }
-    Ok(entries)
+    hours
 }
 
-fn days_back(today: Date, n: i32) -> Vec<Date> {
+/// `split_by_day`, but over local hours-of-day.
+fn split_by_hour(start: OffsetDateTime, end: OffsetDateTime, secs: i64) -> Vec<(usize, i64)> {
+    use crate::util::local;
+    let span = (end - start).whole_seconds();
+    let hour = |t: OffsetDateTime| local(t).hour() as usize;
+    if span <= 0 {
+        return vec![(hour(start), secs)];
+    }
+    let mut out = Vec::new();
+    let mut assigned = 0;
+    let mut cur = start;
+    while cur < end {
+        let l = local(cur);
+        let into_hour = l.minute() as i64 * 60 + l.second() as i64;
+        let next = cur.replace_nanosecond(0).unwrap_or(cur) + Duration::seconds(3600 - into_hour);
+        let piece_end = next.min(end);
+        let share = if piece_end == end {
+            secs - assigned // remainder keeps the sum exact
+        } else {
+            secs * (piece_end - cur).whole_seconds() / span
+        };
+        assigned += share;
+        out.push((hour(cur), share));
+        cur = piece_end;
+    }
+    out
+}
+
+/// Spread `secs` over the local days (config `timezone`) covered by `start..end`, in
+/// proportion to the wall-clock overlap with each day (so paused time is spread evenly).
+fn split_by_day(start: OffsetDateTime, end: OffsetDateTime, secs: i64) -> Vec<(Date, i64)> {
+    use crate::util::{day_start, local};
+    let span = (end - start).whole_seconds();
+    if span <= 0 || local(start).date() == local(end).date() {
+        return vec![(local(start).date(), secs)];
+    }
+    let mut out = Vec::new();
+    let mut assigned = 0;
+    let mut cur = start;
+    while cur < end {
+        let day = local(cur).date();
+        let next_midnight = day.next_day().map(day_start);
+        let piece_end = match next_midnight { Some(m) if m < end => m, _ => end };
+        let share = if piece_end == end {
+            secs - assigned // remainder keeps the sum exact
+        } else {
+            secs * (piece_end - cur).whole_seconds() / span
+        };
+        assigned += share;
+        out.push((day, share));
+        cur = piece_end;
+    }
+    out
+}
+
+/// Days since the start of `d`'s week (config `week_start`).
+fn days_into_week(d: Date) -> u8 {
+    match config::get().week_start.as_str() {
+        "sunday" => d.weekday().number_days_from_sunday(),
+        _ => d.weekday().number_days_from_monday(),
+    }
+}
+
+/// The last `n` weeks up to the current one, oldest first, as (label, days). Monday weeks
+/// are labelled ISO-style (`2026-W41`), Sunday weeks by their first day.
+fn weeks_back(today: Date, n: i64) -> Vec<(String, Vec<Date>)> {
+    let first = today - Duration::days(days_into_week(today) as i64);
+    let sunday = config::get().week_start == "sunday";
+    (0..n)
+        .rev()
+        .map(|k| {
+            let start = first - Duration::weeks(k);
+            let label = if sunday {
+                start.to_string()
+            } else {
+                let (y, w, _) = start.to_iso_week_date();
+                format!("{y}-W{w:02}")
+            };
+            (label, (0..7).map(|i| start + Duration::days(i)).collect())
+        })
+        .collect()
+}
+
+/// The last `n` calendar months up to the current one, oldest first, as (label, days).
+fn months_back(today: Date, n: i32) -> Vec<(String, Vec<Date>)> {
+    let idx = today.year() * 12 + today.month() as i32 - 1;
+    (0..n)
+        .rev()
+        .filter_map(|k| {
+            let (y, m) = ((idx - k).div_euclid(12), (idx - k).rem_euclid(12) as u8 + 1);
+            let month = time::Month::try_from(m).ok()?;
+            let first = Date::from_calendar_date(y, month, 1).ok()?;
+            let days = (0..month.length(y) as i64).map(|i| first + Duration::days(i)).collect();
+            Some((format!("{y}-{m:02}"), days))
+        })
+        .collect()
+}
+
+/// Totals per period with the change vs the period before; the first
+/// period only serves as the baseline and is not returned.
+fn period_rows(per_day: &HashMap<Date, Totals>, periods: &[(String, Vec<Date>)]) -> Vec<(String, Totals, i64)> {
+    let totals: Vec<Totals> = periods.iter().map(|(_, days)| sum_over(per_day, days)).collect();
+    periods
+        .iter()
+        .zip(&totals)
+        .skip(1)
+        .zip(&totals)
+        .map(|(((label, _), t), prev)| (label.clone(), t.clone(), t.total() - prev.total()))
+        .collect()
+}
+
+/// The `n` days ending with `today`, oldest first.
+pub(crate) fn days_back(today: Date, n: i32) -> Vec<Date> {
     (0..n).map(|i| today - Duration::days((n - 1 - i) as i64)).collect()
 }
 
-fn sum_over(per_day: &HashMap<Date, Totals>, days: &[Date]) -> Totals {
+pub(crate) fn sum_over(per_day: &HashMap<Date, Totals>, days: &[Date]) -> Totals {
     let mut t = Totals::default();
     for d in days {
-        if let Some(x) = per_day.get(d) {
-            t.train += x.train;
-            t.battle += x.battle;
-        }
+        if let Some(x) = per_day.get(d) { t.merge(x); }
     }
     t
 }
@@ -131,45 +755,74 @@ fn streak_days<F: Fn(&Totals) -> bool>(per_day: &HashMap<Date, Totals>, end_day:
     let mut count = 0;
     let mut d = end_day;
     loop {
-        let t = per_day.get(&d).copied().unwrap_or_default();
+        let t = per_day.get(&d).cloned().unwrap_or_default();
         if pred(&t) { count += 1; } else { break; }
         d = match d.previous_day() {
             Some(prev) => prev,
             None => break,
         };
-        if count > 365 { break; }
     }
     count
 }
 
-fn parse_duration_seconds(iso: &str) -> i64 {
-    let mut s = iso.trim();
-    if !s.starts_with("PT") { return 0; }
-    s = &s[2..];
-    let mut hours=0; let mut mins=0; let mut secs=0;
-    let mut num = String::new();
-    for ch in s.chars() {
-        if ch.is_ascii_digit() { num.push(ch); continue; }
-        let val = num.parse::<i64>().unwrap_or(0);
-        match ch {
-            'H' => hours = val,
-            'M' => mins  = val,
-            'S' => secs  = val,
-            _ => {}
-        }
-        num.clear();
+/// (label, current, longest) streaks for "any" activity, then each tag, then days meeting
+/// each configured daily goal (`<tag> goal`).
+pub(crate) fn streak_rows(per_day: &HashMap<Date, Totals>, today: Date, all_time: &Totals) -> Vec<(String, i32, i32)> {
+    let mut rows = vec![(
+        "any".to_string(),
+        streak_days(per_day, today, |t| t.total() > 0),
+        longest_streak(per_day, |t| t.total() > 0),
+    )];
+    for tag in all_time.tags() {
+        let current = streak_days(per_day, today, |t| t.get(&tag) > 0);
+        let longest = longest_streak(per_day, |t| t.get(&tag) > 0);
+        rows.push((tag, current, longest));
     }
-    hours*3600 + mins*60 + secs
+    let cfg = config::get();
+    for tag in cfg.goal_tags() {
+        let Some(goal) = cfg.daily_goal(&tag) else { continue; };
+        let met = |t: &Totals| (if tag == "total" { t.total() } else { t.get(&tag) }) >= goal;
+        rows.push((format!("{tag} goal"), streak_days(per_day, today, met), longest_streak(per_day, met)));
+    }
+    rows
 }
 
-fn hm(secs: i64) -> String {
-    let h = secs / 3600;
-    let m = (secs % 3600) / 60;
-    format!("{h}h {m:02}m")
+/// Longest run of consecutive days matching `pred`, over all history.
+fn longest_streak<F: Fn(&Totals) -> bool>(per_day: &HashMap<Date, Totals>, pred: F) -> i32 {
+    let mut days: Vec<Date> = per_day.iter().filter(|(_, t)| pred(t)).map(|(d, _)| *d).collect();
+    days.sort();
+    let (mut best, mut run) = (0, 0);
+    let mut prev: Option<Date> = None;
+    for d in days {
+        run = if prev.and_then(|p| p.next_day()) == Some(d) { run + 1 } else { 1 };
+        best = best.max(run);
+        prev = Some(d);
+    }
+    best
 }
 
 fn minutes(secs: i64) -> i64 { secs / 60 }
 
+/// "train" -> "Train" for headings and chart legends (config `[tags.<tag>] name` wins).
+fn title(tag: &str) -> String {
+    if let Some(name) = config::get().tag_style(tag).and_then(|t| t.name.clone()) {
+        return name;
+    }
+    let mut c = tag.chars();
+    match c.next() {
+        Some(f) => f.to_uppercase().chain(c).collect(),
+        None => String::new(),
+    }
+}
+
+/// `title` with the tag's configured emoji in front, for README text.
+fn tag_label(tag: &str) -> String {
+    match config::get().tag_style(tag).and_then(|t| t.emoji.as_deref()) {
+        Some(e) => format!("{e} {}", title(tag)),
+        None => title(tag),
+    }
+}
+
 fn ascii_area_30d(per_day: &HashMap<Date, Totals>, last30: &[Date], height: usize) -> String {
     if last30.is_empty() || height == 0 {
         return String::new();
@@ -237,51 +890,106 @@ fn ascii_area_30d(per_day: &HashMap<Date, Totals>, last30: &[Date], height: usiz
     out
 }
 
-/// Render activity area chart: raw daily area+line (blue) + single long-trend curve (grey)
-/// Trend control points are coarse-bucketed (TREND_WINDOW_DAYS) and extrapolated to chart edges.
+/// x-axis label for chart column `x` ("Oct 3"); blank past either end.
+fn date_label(dates: &[Date], x: f64) -> String {
+    if x < 0.0 { return String::new(); }
+    match dates.get(x.round() as usize) {
+        Some(d) => format!("{} {}", &d.month().to_string()[..3], d.day()),
+        None => String::new(),
+    }
+}
+
+/// Render activity area chart (PNG for a `.png` path, else SVG): raw daily area+line (blue) + single long-trend curve (grey).
+/// `PerTag` gives each tag its own area+line (palette series colors) and a legend instead
+/// of the combined total and trend; `Only` plots one tag like the total.
+/// The trend is a centered `trend_window`-day moving average, Catmull-Rom smoothed.
 /// Raw values are in minutes but scaled to hours/day for the y-axis.
-pub(crate) fn render_activity_svg(
+pub(crate) fn render_activity(
     per_day: &HashMap<Date, Totals>,
     dates: &[Date],
-    out_path: &str,
-    width: u32,
-    height: u32,
+    out_path: &Path,
+    size: (u32, u32),
+    pal: &Palette,
+    series: ActivitySeries,
 ) -> anyhow::Result<()> {
-    // Tunables
-    const TREND_WINDOW_DAYS: usize = 8;
-    const TREND_SAMPLES_PER_SEGMENT: usize = 50;
-
-    // color palette (user requested)
-    let bg = RGBColor(19, 23, 31);              // rgb(19, 22.5, 30.5) -> rounded
-    let text_col = RGBColor(194, 199, 208);     // #c2c7d0
-    let accent = RGBColor(1, 170, 255);         // #01aaff for the main graph line/points
-    let border_accent = RGBColor(88, 186, 236);
-    let trend_col = RGBColor(210, 20, 20);      // keep the red trend
-
-    // raw per-day minutes
+    if out_path.extension().is_some_and(|e| e == "png") {
+        draw_activity(BitMapBackend::new(out_path, size).into_drawing_area(), per_day, dates, pal, series)?;
+    } else {
+        draw_activity(SVGBackend::new(out_path, size).into_drawing_area(), per_day, dates, pal, series)?;
+    }
+    Ok(())
+}
+
+/// Backend-agnostic body of `render_activity`; consumes (and presents) `root`.
+fn draw_activity<DB: DrawingBackend>(
+    root: DrawingArea<DB, plotters::coord::Shift>,
+    per_day: &HashMap<Date, Totals>,
+    dates: &[Date],
+    pal: &Palette,
+    which: ActivitySeries,
+) -> anyhow::Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let (width, height) = root.dim_in_pixel();
+    // Tunables (config.toml: trend_window, trend_samples)
+    // short charts shrink the window so the average still follows the data
+    let trend_window_days = config::get().trend_window.clamp(1, (dates.len() / 3).max(1));
+    let trend_samples_per_segment = config::get().trend_samples.max(1);
+    let show_cumulative = config::get().show_cumulative;
+
+    let Palette { bg, text: text_col, accent, border: border_accent, trend: trend_col, .. } = *pal;
+
+    // raw per-day minutes (of the one tag for `Only`)
+    let day_secs = |t: &Totals| match which {
+        ActivitySeries::Only(tag) => t.get(tag),
+        _ => t.total(),
+    };
     let vals: Vec<f64> = dates
         .iter()
-        .map(|d| per_day.get(d).map(|t| minutes(t.total()) as f64).unwrap_or(0.0))
+        .map(|d| per_day.get(d).map(|t| minutes(day_secs(t)) as f64).unwrap_or(0.0))
         .collect();
     let n = vals.len();
     if n == 0 {
-        let root = SVGBackend::new(out_path, (width, height)).into_drawing_area();
         root.fill(&bg)?;
         root.present()?;
         return Ok(());
     }
 
+    // per-tag series (minutes), busiest tag first
+    let series: Vec<(String, Vec<f64>)> = if matches!(which, ActivitySeries::PerTag) {
+        let window = sum_over(per_day, dates);
+        window.tags().into_iter().map(|tag| {
+            let v = dates.iter()
+                .map(|d| per_day.get(d).map(|t| minutes(t.get(&tag)) as f64).unwrap_or(0.0))
+                .collect();
+            (tag, v)
+        }).collect()
+    } else {
+        Vec::new()
+    };
+    let domain_vals: Vec<f64> = if series.is_empty() {
+        vals.clone()
+    } else {
+        series.iter().flat_map(|(_, v)| v.iter().copied()).collect()
+    };
+
     // y domain in hours (we keep values in minutes but derive domain in hours)
-    let min_v = vals.iter().cloned().fold(f64::INFINITY, f64::min) / 60.0;
-    let max_v = vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max) / 60.0;
-    let (y0, y1) = if (max_v - min_v).abs() < std::f64::EPSILON {
-        (0.0, max_v.max(0.5))
+    let min_v = domain_vals.iter().cloned().fold(f64::INFINITY, f64::min) / 60.0;
+    let max_v = domain_vals.iter().cloned().fold(f64::NEG_INFINITY, f64::max) / 60.0;
+    // constant series (all days equal, incl. all zero): flat line below the top with 0 at the
+    // bottom, a drawn baseline and no spline
+    let flat = (max_v - min_v).abs() < f64::EPSILON;
+    let (y0, y1) = if flat {
+        (0.0, if max_v > 0.0 { max_v * 1.25 } else { 0.5 })
     } else {
         let pad = (max_v - min_v) * 0.07;
         ((min_v - pad).max(0.0), max_v + pad)
     };
 
-    let root = SVGBackend::new(out_path, (width, height)).into_drawing_area();
+    // degenerate inputs must never reach plotters as NaN/inf ranges
+    let (y0, y1) = if y0.is_finite() && y1.is_finite() && y1 > y0 { (y0, y1) } else { (0.0, 0.5) };
+
     // fill background with chosen dark color
     root.fill(&bg)?;
 
@@ -295,111 +1003,114 @@ pub(crate) fn render_activity_svg(
     ))?;
 
     // raw points scaled to hours for plotting
-    let points_raw: Vec<(f64, f64)> = vals
+    let points_raw: Vec<(f64, f64)> = finite(vals
         .iter()
         .enumerate()
         .map(|(i, &v)| (i as f64, v / 60.0))
-        .collect();
+        .collect());
     let x_upper_f = points_raw.len() as f64;
 
+    // cumulative hours across the window
+    let cumulative: Vec<(f64, f64)> = vals
+        .iter()
+        .scan(0.0, |acc, &v| { *acc += v / 60.0; Some(*acc) })
+        .enumerate()
+        .map(|(i, c)| (i as f64, c))
+        .collect();
+    let cum_max = cumulative.last().map(|p| p.1).unwrap_or(0.0);
+
     // build chart using f64 domain
     let mut chart = ChartBuilder::on(&root)
         .margin(8)
-        .x_label_area_size(0)
+        .x_label_area_size(20)
         .y_label_area_size(50)
-        .right_y_label_area_size(0)
-        .build_cartesian_2d(0f64..x_upper_f, y0..y1)?;
+        .right_y_label_area_size(if show_cumulative { 50 } else { 0 })
+        .build_cartesian_2d(0f64..x_upper_f, y0..y1)?
+        // right axis: running total in hours (only drawn with show_cumulative)
+        .set_secondary_coord(0f64..x_upper_f, 0f64..(cum_max * 1.07).max(1.0));
 
     // configure mesh: keep grid minimal; style labels with text_col
     chart
         .configure_mesh()
         .disable_mesh()
-        .y_desc("hours / day")
+        .y_desc(match which {
+            ActivitySeries::Only(tag) => format!("{} hours / day", title(tag)),
+            _ => "hours / day".to_string(),
+        })
         .axis_desc_style(("sans-serif", 14).into_font().color(&text_col))
         .y_label_formatter(&|v| format!("{:.1}", v))
         .y_label_style(("sans-serif", 10).into_font().color(&text_col))
-        .x_labels((points_raw.len() / 10).max(2))
+        .x_labels((points_raw.len() / 5).clamp(5, 15)) // plotters rounds to a "nice" step, ~10 days at 75
+        .x_label_formatter(&|v| date_label(dates, *v))
         .x_label_style(("sans-serif", 10).into_font().color(&text_col))
         .label_style(("sans-serif", 11).into_font().color(&text_col))
         .axis_style(text_col.stroke_width(1))   // <-- make axis lines use text color
         .draw()?;
 
+    if show_cumulative {
+        chart
+            .configure_secondary_axes()
+            .y_desc("total hours")
+            .axis_desc_style(("sans-serif", 14).into_font().color(&text_col))
+            .y_label_formatter(&|v| format!("{:.0}", v))
+            .label_style(("sans-serif", 10).into_font().color(&text_col))
+            .axis_style(text_col.stroke_width(1))
+            .draw()?;
+        chart.draw_secondary_series(LineSeries::new(cumulative, pal.cumulative.stroke_width(2)))?;
+    }
+
+    if flat {
+        chart.draw_series(std::iter::once(PathElement::new(
+            vec![(0.0, 0.0), (x_upper_f, 0.0)],
+            text_col.mix(0.6).stroke_width(1),
+        )))?;
+    }
+
+    if !series.is_empty() {
+        for (i, (tag, v)) in series.iter().enumerate() {
+            let col = pal.tag_color(tag, i);
+            let pts: Vec<(f64, f64)> = v.iter().enumerate().map(|(x, &m)| (x as f64, m / 60.0)).collect();
+            chart.draw_series(AreaSeries::new(pts.clone(), 0.0, RGBAColor(col.0, col.1, col.2, 0.10)))?;
+            chart
+                .draw_series(LineSeries::new(pts, col.stroke_width(2)))?
+                .label(title(tag))
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 14, y)], col.stroke_width(2)));
+        }
+        chart
+            .configure_series_labels()
+            .background_style(bg.mix(0.8))
+            .border_style(text_col)
+            .label_font(("sans-serif", 11).into_font().color(&text_col))
+            .position(SeriesLabelPosition::UpperLeft)
+            .draw()?;
+
+        root.present()?;
+        return Ok(());
+    }
+
     // area + line + dots using accent color (accent filled area with low alpha)
     let area_fill = RGBAColor(accent.0, accent.1, accent.2, 0.10);
     let line_style = accent.stroke_width(2);
     chart.draw_series(AreaSeries::new(points_raw.clone(), 0.0, area_fill))?;
-    chart.draw_series(LineSeries::new(points_raw.clone().into_iter(), line_style))?;
+    chart.draw_series(LineSeries::new(points_raw.clone(), line_style))?;
     chart.draw_series(points_raw.iter().map(|&(x, y)| {
         Circle::new((x, y), 1, accent.filled())
     }))?;
 
-    // -------- build coarse trend points (minutes -> convert to hours here) --------
-    let mut trend_pts: Vec<(f64, f64)> = Vec::new();
-    let mut i = 0usize;
-    while i < n {
-        let end = (i + TREND_WINDOW_DAYS).min(n);
-        let slice = &vals[i..end];
-        let avg = if slice.is_empty() { 0.0 } else { slice.iter().sum::<f64>() / slice.len() as f64 };
-        let center = (i as f64 + (end - 1) as f64) / 2.0;
-        trend_pts.push((center, avg / 60.0)); // convert to hours
-        i = end;
-    }
-
-    // fallback: denser buckets if too few trend points
-    if trend_pts.len() < 3 && n >= 3 {
-        let mut alt: Vec<(f64, f64)> = Vec::new();
-        let step = (TREND_WINDOW_DAYS as f64 / 2.0).ceil() as usize;
-        let mut j = 0usize;
-        while j < n {
-            let end = (j + step).min(n);
-            let slice = &vals[j..end];
-            let avg = if slice.is_empty() { 0.0 } else { slice.iter().sum::<f64>() / slice.len() as f64 };
-            let center = (j as f64 + (end - 1) as f64) / 2.0;
-            alt.push((center, avg / 60.0));
-            j = end;
-        }
-        if alt.len() >= trend_pts.len() {
-            trend_pts = alt;
-        }
-    }
-
-    // extrapolate endpoints so trend covers full range
-    let x_left = 0.0f64;
-    let x_right = (n - 1) as f64;
-    if trend_pts.is_empty() {
-        trend_pts.push((x_left, vals[0] / 60.0));
-        trend_pts.push((x_right, vals[n - 1] / 60.0));
-    } else {
-        if trend_pts[0].0 > x_left {
-            if trend_pts.len() >= 2 {
-                let p0 = trend_pts[0];
-                let p1 = trend_pts[1];
-                let dx = (p1.0 - p0.0).max(1e-9);
-                let slope = (p1.1 - p0.1) / dx;
-                let y_at_left = p0.1 + slope * (x_left - p0.0);
-                trend_pts.insert(0, (x_left, y_at_left));
-            } else {
-                trend_pts.insert(0, (x_left, vals[0] / 60.0));
-            }
-        } else {
-            trend_pts[0].0 = x_left;
-        }
-
-        let last_idx = trend_pts.len() - 1;
-        if trend_pts[last_idx].0 < x_right {
-            if trend_pts.len() >= 2 {
-                let p_last = trend_pts[last_idx];
-                let p_prev = trend_pts[last_idx - 1];
-                let dx = (p_last.0 - p_prev.0).max(1e-9);
-                let slope = (p_last.1 - p_prev.1) / dx;
-                let y_at_right = p_last.1 + slope * (x_right - p_last.0);
-                trend_pts.push((x_right, y_at_right));
-            } else {
-                trend_pts.push((x_right, vals[n - 1] / 60.0));
-            }
-        } else {
-            trend_pts[last_idx].0 = x_right;
-        }
+    // -------- trend: centered moving average (minutes -> hours) --------
+    // Each day averages the `trend_window` days around it (truncated at the chart edges),
+    // so the curve always spans the full range. Control points are taken every half window
+    // (plus the last day) to keep the spline smooth without thousands of samples.
+    let half = trend_window_days / 2;
+    let ma = |c: usize| -> f64 {
+        let lo = c.saturating_sub(half);
+        let hi = (c + trend_window_days - half).min(n);
+        vals[lo..hi].iter().sum::<f64>() / (hi - lo) as f64
+    };
+    let stride = half.max(1);
+    let mut trend_pts: Vec<(f64, f64)> = (0..n).step_by(stride).map(|c| (c as f64, ma(c) / 60.0)).collect();
+    if !(n - 1).is_multiple_of(stride) {
+        trend_pts.push(((n - 1) as f64, ma(n - 1) / 60.0));
     }
 
     // Catmull-Rom spline (dense sampling)
@@ -427,11 +1138,11 @@ pub(crate) fn render_activity_svg(
         out
     }
 
-    let trend_curve = if trend_pts.len() >= 2 {
-        catmull_rom_spline(&trend_pts, TREND_SAMPLES_PER_SEGMENT)
+    let trend_curve = finite(if trend_pts.len() >= 2 && !flat {
+        catmull_rom_spline(&trend_pts, trend_samples_per_segment)
     } else {
         trend_pts.clone()
-    };
+    });
 
     // draw trend (red) on top
     chart.draw_series(std::iter::once(PathElement::new(
@@ -439,28 +1150,294 @@ pub(crate) fn render_activity_svg(
         trend_col.stroke_width(4),
     )))?;
 
+    // present() writes the file; the caller syncs it once the backend is dropped
+    root.present()?;
+    Ok(())
+}
+
+/// Drop points with a NaN/infinite coordinate before they are drawn.
+fn finite(pts: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    pts.into_iter().filter(|(x, y)| x.is_finite() && y.is_finite()).collect()
+}
+
+/// Render a GitHub-style calendar heatmap: one column per week (rows from config `week_start`),
+/// cell intensity bucketed into quarters of the busiest day. Empty days use the background.
+pub(crate) fn render_heatmap_svg(
+    per_day: &HashMap<Date, Totals>,
+    dates: &[Date],
+    out_path: &Path,
+    cell: u32,
+    pal: &Palette,
+) -> anyhow::Result<()> {
+    const GAP: u32 = 3;
+    const MARGIN: u32 = 12;
+
+    let Palette { bg, accent, border: border_accent, empty: empty_outline, .. } = *pal;
+
+    let offset = dates.first().map(|d| days_into_week(*d) as usize).unwrap_or(0);
+    let weeks = (dates.len() + offset).div_ceil(7).max(1) as u32;
+    let width = MARGIN * 2 + weeks * (cell + GAP) - GAP;
+    let height = MARGIN * 2 + 7 * (cell + GAP) - GAP;
+
+    let root = SVGBackend::new(out_path, (width, height)).into_drawing_area();
+    root.fill(&bg)?;
+    root.draw(&Rectangle::new(
+        [(0, 0), (width as i32 - 1, height as i32 - 1)],
+        ShapeStyle { color: border_accent.to_rgba(), filled: false, stroke_width: 2 },
+    ))?;
+
+    let mins: Vec<i64> = dates
+        .iter()
+        .map(|d| per_day.get(d).map(|t| minutes(t.total())).unwrap_or(0))
+        .collect();
+    let max_m = mins.iter().copied().max().unwrap_or(0);
+
+    // blend bg -> accent by intensity level (1..=4)
+    let shade = |level: i64| {
+        let f = level as f64 / 4.0;
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        RGBColor(mix(bg.0, accent.0), mix(bg.1, accent.1), mix(bg.2, accent.2))
+    };
+
+    for (i, &m) in mins.iter().enumerate() {
+        let slot = i + offset;
+        let x = (MARGIN + (slot / 7) as u32 * (cell + GAP)) as i32;
+        let y = (MARGIN + (slot % 7) as u32 * (cell + GAP)) as i32;
+        let rect = [(x, y), (x + cell as i32, y + cell as i32)];
+        if m <= 0 || max_m <= 0 {
+            root.draw(&Rectangle::new(rect, bg.filled()))?;
+            root.draw(&Rectangle::new(rect, empty_outline.stroke_width(1)))?;
+        } else {
+            let level = ((m * 4 + max_m - 1) / max_m).clamp(1, 4);
+            root.draw(&Rectangle::new(rect, shade(level).filled()))?;
+        }
+    }
+
+    root.present()?;
+    drop(root);
+    Ok(())
+}
+
+/// 24 bars of all-time hours per hour of day (local), accent colored.
+pub(crate) fn render_hours_svg(
+    hours: &[i64; 24],
+    out_path: &Path,
+    (width, height): (u32, u32),
+    pal: &Palette,
+) -> anyhow::Result<()> {
+    let Palette { bg, text: text_col, accent, .. } = *pal;
+    let max_h = hours.iter().copied().max().unwrap_or(0) as f64 / 3600.0;
+
+    let root = SVGBackend::new(out_path, (width, height)).into_drawing_area();
+    root.fill(&bg)?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(8)
+        .x_label_area_size(20)
+        .y_label_area_size(50)
+        .build_cartesian_2d((0u32..24u32).into_segmented(), 0f64..(max_h * 1.07).max(1.0))?;
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .y_desc("hours")
+        .axis_desc_style(("sans-serif", 14).into_font().color(&text_col))
+        .y_label_formatter(&|v| format!("{:.0}", v))
+        .x_labels(12)
+        .x_label_formatter(&|v| match v {
+            SegmentValue::CenterOf(h) | SegmentValue::Exact(h) => format!("{h:02}"),
+            SegmentValue::Last => String::new(),
+        })
+        .label_style(("sans-serif", 10).into_font().color(&text_col))
+        .axis_style(text_col.stroke_width(1))
+        .draw()?;
+    // plain rects rather than `Histogram`, whose hash-map order makes the SVG churn in git
+    chart.draw_series((0u32..24).map(|h| {
+        let y = hours[h as usize] as f64 / 3600.0;
+        let mut bar = Rectangle::new(
+            [(SegmentValue::Exact(h), 0.0), (SegmentValue::Exact(h + 1), y)],
+            accent.filled(),
+        );
+        bar.set_margin(0, 0, 2, 2);
+        bar
+    }))?;
+
+    root.present()?;
+    drop(chart);
+    drop(root);
+    Ok(())
+}
+
+/// What the profile card shows: one calendar year up to `today`.
+pub(crate) struct CardStats {
+    pub year: i32,
+    pub total_secs: i64,
+    /// Tag with the most time this year.
+    pub top_tag: Option<(String, i64)>,
+    /// Longest run of active days within the year.
+    pub longest_streak: i32,
+    /// Hours per week (Jan 1-7, 8-14, ...), for the sparkline.
+    pub weekly_hours: Vec<f64>,
+}
+
+impl CardStats {
+    pub(crate) fn year(per_day: &HashMap<Date, Totals>, today: Date) -> Self {
+        let days: Vec<Date> = std::iter::successors(Date::from_ordinal_date(today.year(), 1).ok(), |d| d.next_day())
+            .take_while(|d| *d <= today)
+            .collect();
+        let year_days: HashMap<Date, Totals> = days.iter()
+            .filter_map(|d| per_day.get(d).map(|t| (*d, t.clone())))
+            .collect();
+        let totals = sum_over(per_day, &days);
+        CardStats {
+            year: today.year(),
+            total_secs: totals.total(),
+            top_tag: totals.tags().into_iter().next().map(|t| { let secs = totals.get(&t); (t, secs) }),
+            longest_streak: longest_streak(&year_days, |t| t.total() > 0),
+            weekly_hours: days.chunks(7).map(|w| sum_over(per_day, w).total() as f64 / 3600.0).collect(),
+        }
+    }
+}
+
+/// A GitHub-profile style card: the year's hours, top tag, longest streak and a weekly
+/// sparkline. Fields read "—" (and the sparkline stays flat) until there is data.
+pub(crate) fn render_summary_card_svg(stats: &CardStats, out_path: &Path, pal: &Palette) -> anyhow::Result<()> {
+    const W: i32 = 420;
+    const H: i32 = 170;
+    const PAD: i32 = 18;
+    let Palette { bg, text: text_col, accent, border, empty, .. } = *pal;
+
+    let root = SVGBackend::new(out_path, (W as u32, H as u32)).into_drawing_area();
+    root.fill(&bg)?;
+    root.draw(&Rectangle::new(
+        [(0, 0), (W - 1, H - 1)],
+        ShapeStyle { color: border.to_rgba(), filled: false, stroke_width: 2 },
+    ))?;
+
+    let font = |size: i32| ("sans-serif", size).into_font().color(&text_col);
+    root.draw(&Text::new(format!("{} in blazectl", stats.year), (PAD, PAD), font(18)))?;
+
+    let blank = || "—".to_string();
+    let fields = [
+        ("Total", if stats.total_secs > 0 { hm(stats.total_secs) } else { blank() }),
+        ("Top tag", stats.top_tag.as_ref().map_or_else(blank, |(t, secs)| format!("{} ({})", title(t), hm(*secs)))),
+        ("Longest streak", if stats.longest_streak > 0 { format!("{}d", stats.longest_streak) } else { blank() }),
+    ];
+    for (i, (label, value)) in fields.iter().enumerate() {
+        let y = PAD + 32 + i as i32 * 22;
+        root.draw(&Text::new(*label, (PAD, y), font(13)))?;
+        root.draw(&Text::new(value.as_str(), (PAD + 130, y), ("sans-serif", 13).into_font().color(&accent)))?;
+    }
+
+    // sparkline along the bottom, scaled to the busiest week
+    let (left, right, base, top) = (PAD, W - PAD, H - PAD, H - PAD - 36);
+    let max = stats.weekly_hours.iter().copied().fold(0.0, f64::max);
+    root.draw(&PathElement::new(vec![(left, base), (right, base)], empty.stroke_width(1)))?;
+    if max > 0.0 && stats.weekly_hours.len() > 1 {
+        let step = (right - left) as f64 / (stats.weekly_hours.len() - 1) as f64;
+        let pts: Vec<(i32, i32)> = stats.weekly_hours.iter().enumerate()
+            .map(|(i, h)| (left + (i as f64 * step).round() as i32, base - (h / max * (base - top) as f64).round() as i32))
+            .collect();
+        root.draw(&PathElement::new(pts, accent.stroke_width(2)))?;
+    }
+
     root.present()?;
+    drop(root);
+    Ok(())
+}
 
-    let f = File::open(out_path)?;
-    f.sync_all()?;
+/// "Train 62% / Battle 38%" plus a 20-char ratio bar; "no data" when empty.
+fn tag_split(t: &Totals, tags: &[String]) -> String {
+    const WIDTH: i64 = 20;
+    const FILLS: [char; 4] = ['█', '▓', '▒', '░'];
+    let total = t.total();
+    if total <= 0 { return "no data".to_string(); }
+    let parts: Vec<String> = tags
+        .iter()
+        .map(|tag| format!("{} {}%", title(tag), (t.get(tag) * 100 + total / 2) / total))
+        .collect();
+    let mut bar = String::new();
+    let mut used = 0;
+    for (i, tag) in tags.iter().enumerate() {
+        let cells = if i + 1 == tags.len() { WIDTH - used } else { t.get(tag) * WIDTH / total };
+        bar.extend(std::iter::repeat_n(FILLS[i % FILLS.len()], cells.max(0) as usize));
+        used += cells;
+    }
+    format!("{} `{bar}`", parts.join(" / "))
+}
 
+/// `█████░░░░░ 2h 00m / 4h 00m — 2h 00m to go` (or `✅ goal met`).
+fn goal_progress(done: i64, goal: i64) -> String {
+    const WIDTH: i64 = 20;
+    let filled = (done * WIDTH / goal.max(1)).clamp(0, WIDTH) as usize;
+    let bar = format!("{}{}", "█".repeat(filled), "░".repeat(WIDTH as usize - filled));
+    let status = if done >= goal { "✅ goal met".to_string() } else { format!("{} to go", hm(goal - done)) };
+    format!("`{bar}` {} / {} — {status}", hm(done), hm(goal))
+}
+
+/// ✅ (all daily goals met) / ⚠️ (the furthest behind is at least half way) / ❌ for a
+/// day's totals against (tag, goal seconds); `None` without goals.
+fn goal_mark(t: &Totals, goals: &[(String, i64)]) -> Option<&'static str> {
+    let worst = goals.iter()
+        .map(|(tag, goal)| {
+            let done = if tag == "total" { t.total() } else { t.get(tag) };
+            done as f64 / (*goal).max(1) as f64
+        })
+        .reduce(f64::min)?;
+    Some(match worst {
+        r if r >= 1.0 => "✅",
+        r if r >= 0.5 => "⚠️",
+        _ => "❌",
+    })
+}
+
+/// Markdown table of (label, totals, delta vs previous) rows, newest last.
+fn write_period_table(s: &mut String, label: &str, rows: &[(String, Totals, i64)], tags: &[String]) -> std::fmt::Result {
+    use std::fmt::Write;
+    let mut header = format!("| {label} |");
+    let mut sep = format!("|{}|", "-".repeat(label.len() + 2));
+    for tag in tags {
+        let name = tag_label(tag);
+        header.push_str(&format!(" {name} |"));
+        sep.push_str(&format!("{}|", "-".repeat(name.chars().count() + 2)));
+    }
+    writeln!(s, "{header} Total | Δ prev |")?;
+    writeln!(s, "{sep}-------|--------|")?;
+    for (name, t, delta) in rows {
+        let mut line = format!("| {name} |");
+        for tag in tags {
+            line.push_str(&format!(" {} |", hm(t.get(tag))));
+        }
+        let sign = if *delta < 0 { "-" } else { "+" };
+        writeln!(s, "{line} {} | {sign}{} |", hm(t.total()), hm(delta.abs()))?;
+    }
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_md(
     now: OffsetDateTime,
+    range: Option<&str>,
+    best: &[(String, (Date, i64))],
+    (avg30, avg_all): (Option<i64>, Option<i64>),
     all_time: Totals,
     _last7: &Totals,
     _last30: &Totals,
     last30_tag: &Totals,
     daily7: &[(Date, Totals)],
-    _streak_any: i32,
-    _streak_train: i32,
-    _streak_battle: i32,
+    today_tot: &Totals,
+    week_tot: &Totals,
+    weekly: &[(String, Totals, i64)],
+    monthly: &[(String, Totals, i64)],
+    streaks: &[(String, i32, i32)],
+    chart_days: u32,
+    chart_tag: Option<&str>,
+    activity_img: &str,
+    heatmap_img: &str,
+    hours_img: &str,
+    summary_img: &str,
+    hours: &[i64; 24],
     _ascii_area: &str,
 ) -> anyhow::Result<String> {
     use std::fmt::Write;
-    let version = env!("CARGO_PKG_VERSION");
 
     let mut s = String::new();
 
@@ -475,38 +1452,157 @@ fn render_md(
     writeln!(s)?;
 
     writeln!(s, "- **Updated (UTC):** {}", iso(now))?;
+    if let Some(r) = range {
+        writeln!(s, "- **Range:** {r}")?;
+    }
+    // nothing to chart yet: a pointer instead of empty tables and flat graphs
+    if all_time.session_total() == 0 && all_time.total() == 0 {
+        writeln!(s)?;
+        match range {
+            Some(_) => writeln!(s, "No sessions logged in this range.")?,
+            None => writeln!(s, "No sessions logged yet — run `blazectl start train`, then `blazectl stop train`.")?,
+        }
+        writeln!(s)?;
+        write_about(&mut s)?;
+        return Ok(s);
+    }
+    let tags = all_time.tags();
+
     writeln!(s, "- **All-time (Total):** {}", hm(all_time.total()))?;
-    writeln!(s, "- **All-time (Train):** {}", hm(all_time.train))?;
-    writeln!(s, "- **All-time (Battle):** {}", hm(all_time.battle))?;
+    for tag in &tags {
+        writeln!(s, "- **All-time ({}):** {}", tag_label(tag), hm(all_time.get(tag)))?;
+    }
+    // average session = seconds / sessions; nothing logged yet reads "no sessions"
+    let sessions = |n: i64, secs: i64| match n {
+        0 => "no sessions".to_string(),
+        _ => format!("{n} (avg {})", hm(secs / n)),
+    };
+    writeln!(s, "- **Sessions (Total):** {}", sessions(all_time.session_total(), all_time.total()))?;
+    for tag in &tags {
+        writeln!(s, "- **Sessions ({}):** {}", tag_label(tag), sessions(all_time.session_count(tag), all_time.get(tag)))?;
+    }
+    let avg = |a: Option<i64>| a.map_or("no active days".to_string(), hm);
+    writeln!(s, "- **Avg per active day (30d):** {}", avg(avg30))?;
+    writeln!(s, "- **Avg per active day (all-time):** {}", avg(avg_all))?;
+    for (tag, (day, secs)) in best {
+        let which = if tag == "total" { String::new() } else { format!(" ({})", tag_label(tag)) };
+        writeln!(s, "- **Best day{which}:** {day} — {}", hm(*secs))?;
+    }
+    writeln!(s)?;
+    writeln!(s, "![Year summary]({summary_img})")?;
     writeln!(s)?;
 
+    // Goals (config [goals])
+    let cfg = config::get();
+    let goal_tags = cfg.goal_tags();
+    if !goal_tags.is_empty() {
+        writeln!(s, "## Goals")?;
+        for tag in &goal_tags {
+            let done = |t: &Totals| if tag == "total" { t.total() } else { t.get(tag) };
+            if let Some(goal) = cfg.daily_goal(tag) {
+                writeln!(s, "- {} today: {}", tag_label(tag), goal_progress(done(today_tot), goal))?;
+            }
+            if let Some(goal) = cfg.weekly_goal(tag) {
+                writeln!(s, "- {} this week: {}", tag_label(tag), goal_progress(done(week_tot), goal))?;
+            }
+        }
+        writeln!(s)?;
+    }
+
     // Per-tag 30d
     writeln!(s, "## Per-tag (last 30d)")?;
-    writeln!(s, "- Train: {}", hm(last30_tag.train))?;
-    writeln!(s, "- Battle: {}", hm(last30_tag.battle))?;
+    for tag in &tags {
+        writeln!(s, "- {}: {}", tag_label(tag), hm(last30_tag.get(tag)))?;
+    }
+    writeln!(s, "- Split: {}", tag_split(last30_tag, &tags))?;
     writeln!(s)?;
 
     // Daily (last 7 days)
     writeln!(s, "## Daily (last 7 days)")?;
-    writeln!(s, "| Date       | Train | Battle | Total |")?;
-    writeln!(s, "|------------|-------|--------|-------|")?;
+    let mut header = String::from("| Date       |");
+    let mut sep = String::from("|------------|");
+    for tag in &tags {
+        let name = tag_label(tag);
+        header.push_str(&format!(" {name} |"));
+        sep.push_str(&format!("{}|", "-".repeat(name.chars().count() + 2)));
+    }
+    // goal column only when some daily goal is configured
+    let daily_goals: Vec<(String, i64)> = config::get().goal_tags().into_iter()
+        .filter_map(|tag| config::get().daily_goal(&tag).map(|g| (tag, g)))
+        .collect();
+    let (goal_head, goal_sep) = if daily_goals.is_empty() { ("", "") } else { (" Goal |", "------|") };
+    writeln!(s, "{header} Total |{goal_head}")?;
+    writeln!(s, "{sep}-------|{goal_sep}")?;
     let mut rows = daily7.to_vec();
     rows.sort_by_key(|(d, _)| *d);
     for (d, t) in rows {
-        writeln!(
-            s,
-            "| {} | {:>5} | {:>6} | {:>5} |",
-            d, hm(t.train), hm(t.battle), hm(t.total())
-        )?;
+        let mut line = format!("| {d} |");
+        for tag in &tags {
+            line.push_str(&format!(" {:>w$} |", hms(t.get(tag)), w = tag_label(tag).chars().count()));
+        }
+        let goal = goal_mark(&t, &daily_goals).map_or(String::new(), |m| format!(" {m} |"));
+        writeln!(s, "{line} {:>5} |{goal}", hms(t.total()))?;
     }
     writeln!(s)?;
 
-    // Image-embedded Activity Graph (75 days)
+    // Week-over-week / month-over-month
+    match config::get().week_start.as_str() {
+        "sunday" => writeln!(s, "## Weekly (last 8 weeks, Sun–Sat)")?,
+        _ => writeln!(s, "## Weekly (last 8 ISO weeks)")?,
+    }
+    write_period_table(&mut s, "Week", weekly, &tags)?;
+    writeln!(s)?;
+    writeln!(s, "## Monthly (last 12 months)")?;
+    write_period_table(&mut s, "Month", monthly, &tags)?;
+    writeln!(s)?;
+
+    // Streaks (days)
+    writeln!(s, "## Streaks")?;
+    writeln!(s, "| Streak | Current | Longest |")?;
+    writeln!(s, "|--------|---------|---------|")?;
+    for (label, current, longest) in streaks {
+        writeln!(s, "| {} | {current}d | {longest}d |", tag_label(label))?;
+    }
+    writeln!(s)?;
+
+    // Image-embedded Activity Graph (chart_days, default 75)
     writeln!(s, "## Activity Graph")?;
-    writeln!(s, "![Activity Graph](assets/activity.svg)")?;
-    writeln!(s, "(Total hours per day for the last 75 days)")?;
+    writeln!(s, "![Activity Graph]({activity_img})")?;
+    match chart_tag {
+        Some(tag) => writeln!(s, "({} hours per day for the last {chart_days} days)", tag_label(tag))?,
+        None => writeln!(s, "(Total hours per day for the last {chart_days} days)")?,
+    }
+    writeln!(s)?;
+
+    // Contribution-style heatmap (last year)
+    writeln!(s, "## Heatmap")?;
+    writeln!(s, "![Heatmap]({heatmap_img})")?;
+    writeln!(s, "(Total minutes per day for the last year, one column per week)")?;
+    writeln!(s)?;
+
+    // Hour-of-day histogram (all time)
+    writeln!(s, "## Time of Day")?;
+    writeln!(s, "![Time of Day]({hours_img})")?;
+    match (0..24).max_by_key(|&h| (hours[h], std::cmp::Reverse(h))) {
+        Some(h) if hours[h] > 0 => writeln!(
+            s,
+            "(All-time hours per hour of day, {}; busiest {h:02}:00-{:02}:00 with {})",
+            crate::util::zone_label(), (h + 1) % 24, hm(hours[h]),
+        )?,
+        _ => writeln!(s, "(All-time hours per hour of day; no data yet)")?,
+    }
     writeln!(s)?;
 
+    write_about(&mut s)?;
+    Ok(s)
+}
+
+/// Installation, usage, license and footer: the part of the README that doesn't depend
+/// on the data.
+fn write_about(s: &mut String) -> std::fmt::Result {
+    use std::fmt::Write;
+    let version = env!("CARGO_PKG_VERSION");
+
     // Installation (clear steps)
     writeln!(s, "## Installation")?;
     writeln!(s, "1. **Install Rust**")?;
@@ -541,7 +1637,9 @@ fn render_md(
     writeln!(s, "blazectl render-readme")?;
     writeln!(s, "```")?;
     writeln!(s, "Data is stored in `.blaze/track-YYYY-MM.jsonl` (UTC timestamps, ISO-8601 durations).")?;
+    writeln!(s, "Run from anywhere with `--dir path/to/.blaze` or `BLAZE_HOME`.")?;
     writeln!(s, "Configure keybindings externally (WM/OS).")?;
+    writeln!(s, "Exit codes: `0` ok, `1` error, `3` nothing running (`status`, `stop`), `4` README stale (`check-freshness`).")?;
     writeln!(s)?;
 
     // License
@@ -554,6 +1652,5 @@ fn render_md(
     writeln!(s)?;
     writeln!(s, "Generated by **blazectl v{}**.", version)?;
     writeln!(s, "Created by [0xh4ty](https://github.com/0xh4ty) for fellow warriors.")?;
-
-    Ok(s)
+    Ok(())
 }
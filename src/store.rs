@@ -1,9 +1,13 @@
-use std::{fs::{OpenOptions, self}, io::Write, path::PathBuf};
-use anyhow::Result;
+use std::{fs::{OpenOptions, self}, io::Write, path::{Path, PathBuf}};
+use std::sync::{OnceLock, atomic::{AtomicBool, Ordering}};
+use anyhow::{anyhow, Result};
 use serde::Serialize;
-use time::{Duration, OffsetDateTime};
+use time::{Duration, OffsetDateTime, UtcOffset};
 
-use crate::util::now_utc;
+use crate::util::{iso, parse_iso, parse_duration_seconds};
+
+/// Written as `v` on every logged line; lines from before it existed have none.
+pub const FORMAT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(Serialize)]
 pub struct Entry {
@@ -12,36 +16,501 @@ pub struct Entry {
     pub end: String,
     #[serde(serialize_with="ser_dur_iso")]
     pub duration: Duration,
+    #[serde(skip_serializing_if="Option::is_none")]
+    pub note: Option<String>,
+    /// blazectl version that wrote the line (`FORMAT_VERSION`), so readers can tell
+    /// formats apart once they change.
+    pub v: &'static str,
 }
 
 fn ser_dur_iso<S: serde::Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
-    // simple ISO-8601 "PT...H...M...S" without days for v0
-    let mut secs = d.whole_seconds();
-    if secs < 0 { secs = 0; } // clamp
-    let h = secs / 3600;
+    s.serialize_str(&dur_iso(d.whole_seconds()))
+}
+
+/// ISO-8601 "P{D}DT{H}H{M}M{S}S"; the day part is omitted under 24h ("PT...")
+pub fn dur_iso(secs: i64) -> String {
+    let secs = secs.max(0); // clamp
+    let days = secs / 86400;
+    let h = (secs % 86400) / 3600;
     let m = (secs % 3600) / 60;
     let s_rem = secs % 60;
-    let iso = format!("PT{}H{}M{}S", h, m, s_rem);
-    s.serialize_str(&iso)
+    if days > 0 {
+        format!("P{}DT{}H{}M{}S", days, h, m, s_rem)
+    } else {
+        format!("PT{}H{}M{}S", h, m, s_rem)
+    }
+}
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Data directory from `--dir` / `BLAZE_HOME`; set once at startup.
+pub fn set_dir(p: PathBuf) { let _ = DIR.set(p); }
+
+/// Data directory (default `.blaze` in the CWD).
+pub fn dir() -> PathBuf {
+    DIR.get().cloned().unwrap_or_else(|| PathBuf::from(".blaze"))
+}
+
+/// Where README.md, assets/ and the git repo live: the data directory's parent.
+pub fn root() -> PathBuf {
+    match dir().parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+static SYNC: AtomicBool = AtomicBool::new(false);
+
+/// Opt-in durability (`--sync` / `BLAZE_SYNC=1`): fsync logs and rendered files.
+pub fn set_sync(on: bool) { SYNC.store(on, Ordering::Relaxed); }
+pub fn sync_enabled() -> bool { SYNC.load(Ordering::Relaxed) }
+
+/// Write a whole file and close it before returning, fsyncing when durable mode is on.
+pub fn write_file(path: &Path, contents: &str) -> Result<()> {
+    let mut f = fs::File::create(path)?;
+    f.write_all(contents.as_bytes())?;
+    f.flush()?;
+    if sync_enabled() { f.sync_all()?; }
+    drop(f);
+    crate::util::trace(format_args!("wrote {}", path.display()));
+    Ok(())
+}
+
+/// fsync a file something else wrote and closed (rendered charts), in durable mode.
+pub fn sync_file(path: &Path) -> Result<()> {
+    if sync_enabled() { fs::File::open(path)?.sync_all()?; }
+    Ok(())
 }
 
 pub fn ensure_dirs() -> Result<()> {
-    fs::create_dir_all(".blaze")?;
+    fs::create_dir_all(dir())?;
     Ok(())
 }
 
 fn month_file(dt: OffsetDateTime) -> PathBuf {
+    let dt = dt.to_offset(UtcOffset::UTC);
     let ym = format!("{}-{:02}", dt.year(), u8::try_from(dt.month() as i32).unwrap_or(1));
-    PathBuf::from(format!(".blaze/track-{ym}.jsonl"))
+    dir().join(format!("track-{ym}.jsonl"))
+}
+
+/// All `track-*.jsonl` files in the data dir, sorted by name (i.e. month).
+pub fn track_files() -> Vec<PathBuf> { track_files_in(&dir()) }
+
+fn track_files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(rd) => rd
+            .flatten()
+            .filter(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                name.starts_with("track-") && name.ends_with(".jsonl")
+            })
+            .map(|e| e.path())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// Every parseable JSONL line across all month files (malformed lines are skipped).
+fn read_raw_entries() -> Result<Vec<serde_json::Value>> { read_raw_entries_in(&dir()) }
+
+fn read_raw_entries_in(dir: &Path) -> Result<Vec<serde_json::Value>> {
+    Ok(track_files_in(dir).iter().flat_map(|p| read_raw_file(p)).collect())
+}
+
+fn read_raw_file(path: &Path) -> Vec<serde_json::Value> {
+    let Ok(s) = fs::read_to_string(path) else { return Vec::new(); };
+    s.lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .collect()
+}
+
+/// A logged session as read back from the month files.
+#[derive(Clone, Debug)]
+pub struct LoggedEntry {
+    pub activity: String,
+    pub start: OffsetDateTime,
+    /// `None` if missing or unparsable; the duration still counts.
+    pub end: Option<OffsetDateTime>,
+    pub duration_seconds: i64,
+    pub note: Option<String>,
+}
+
+impl LoggedEntry {
+    /// Parse one JSONL value; `Err` says why it can't be used (see `doctor`).
+    pub fn from_value(v: &serde_json::Value) -> std::result::Result<LoggedEntry, String> {
+        let field = |k: &str| v.get(k).and_then(|x| x.as_str());
+        let activity = field("activity").unwrap_or("").to_string();
+        let start_iso = field("start").unwrap_or("");
+        let start = parse_iso(start_iso).map_err(|_| format!("{activity} entry: bad start {start_iso:?}"))?;
+        let dur = field("duration").unwrap_or("PT0S");
+        let duration_seconds = parse_duration_seconds(dur)
+            .ok_or_else(|| format!("{activity} entry at {start_iso}: bad duration {dur:?}"))?;
+        Ok(LoggedEntry {
+            activity,
+            start,
+            end: field("end").and_then(|e| parse_iso(e).ok()),
+            duration_seconds,
+            note: field("note").map(str::to_string),
+        })
+    }
+}
+
+/// Every usable entry across all month files, in file order. Unusable lines are
+/// skipped with a warning.
+pub fn read_all_entries() -> Result<Vec<LoggedEntry>> { read_entries_in(&dir()) }
+
+/// `read_all_entries` for the month files in `dir` instead of the configured data dir.
+pub fn read_entries_in(dir: &Path) -> Result<Vec<LoggedEntry>> {
+    Ok(usable(&read_raw_entries_in(dir)?))
 }
 
+/// The usable entries of one month file.
+pub fn read_entries_file(path: &Path) -> Vec<LoggedEntry> {
+    usable(&read_raw_file(path))
+}
+
+fn usable(raw: &[serde_json::Value]) -> Vec<LoggedEntry> {
+    raw.iter()
+        .filter_map(|v| LoggedEntry::from_value(v).map_err(|e| crate::util::warn(format_args!("skipping {e}"))).ok())
+        .collect()
+}
+
+/// All entries sorted by start, as a JSON array (`json`) or
+/// `activity,start,end,duration_seconds,note` rows (`csv`).
+pub fn export(format: &str) -> Result<String> {
+    let field = |v: &serde_json::Value, k: &str| v.get(k).and_then(|x| x.as_str()).unwrap_or("").to_string();
+    // raw values, so the export keeps every line exactly as logged
+    let mut entries = read_raw_entries()?;
+    entries.sort_by_key(|v| parse_iso(&field(v, "start")).ok());
+
+    match format {
+        "json" => Ok(serde_json::to_string_pretty(&entries)? + "\n"),
+        "csv" => {
+            let mut out = String::from("activity,start,end,duration_seconds,note\n");
+            for v in &entries {
+                let secs = parse_duration_seconds(&field(v, "duration")).unwrap_or(0);
+                let row = [field(v, "activity"), field(v, "start"), field(v, "end"), secs.to_string(), field(v, "note")];
+                let row: Vec<String> = row.iter().map(|c| csv_field(c)).collect();
+                out.push_str(&row.join(","));
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        _ => Err(anyhow!("unknown format: {format} (use json|csv)")),
+    }
+}
+
+/// Quote a CSV cell when it contains a separator, quote or newline.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Split CSV text into records, honoring quoted cells (`""` escapes, embedded newlines).
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let (mut row, mut cell) = (Vec::new(), String::new());
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => { cell.push('"'); chars.next(); }
+            (true, '"') => quoted = false,
+            (true, _) => cell.push(c),
+            (false, '"') => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut cell)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, _) => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    rows
+}
+
+/// Import `tag,start,end[,note]` rows, skipping ones already logged (same tag + start).
+/// Returns (imported, duplicates, invalid).
+pub fn import_csv(text: &str) -> Result<(usize, usize, usize)> {
+    let mut seen: std::collections::HashSet<(String, OffsetDateTime)> = read_all_entries()?
+        .into_iter()
+        .map(|e| (e.activity, e.start))
+        .collect();
+
+    let (mut imported, mut dups, mut invalid) = (0, 0, 0);
+    for (i, row) in parse_csv(text).into_iter().enumerate() {
+        let cell = |n: usize| row.get(n).map(|c| c.trim()).unwrap_or("");
+        if i == 0 && matches!(cell(0), "tag" | "activity") { continue; } // header
+        let entry = crate::active::normalize_tag(cell(0))
+            .and_then(|tag| manual_entry(&tag, cell(1), Some(cell(2)), None));
+        let mut entry = match entry {
+            Ok(e) => e,
+            Err(e) => { eprintln!("import: row {}: {e}", i + 1); invalid += 1; continue; }
+        };
+        if !cell(3).is_empty() { entry.note = Some(cell(3).to_string()); }
+        if !seen.insert((entry.activity.clone(), parse_iso(&entry.start)?)) {
+            dups += 1;
+            continue;
+        }
+        append_entry(&entry)?;
+        imported += 1;
+    }
+    Ok((imported, dups, invalid))
+}
+
+/// Check every month file for malformed lines, missing fields and end < start.
+/// With `fix`, bad lines are dropped and missing/bad durations recomputed from
+/// start/end; the original goes to `<file>.bak` and the file is rewritten atomically.
+/// Returns one `file:line: problem` message per issue.
+pub fn doctor(fix: bool) -> Result<Vec<String>> {
+    let mut issues = Vec::new();
+    for path in track_files() {
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let text = fs::read_to_string(&path)?;
+        let mut kept = String::new();
+        let mut changed = false;
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() { continue; }
+            let mut report = |msg: String| issues.push(format!("{name}:{}: {msg}", i + 1));
+            let mut v = match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(v) if v.is_object() => v,
+                _ => { report("malformed JSON -> drop".into()); changed = true; continue; }
+            };
+            let field = |k: &str| v.get(k).and_then(|x| x.as_str()).map(str::to_string);
+            let (Some(_), Some(st), Some(en)) = (field("activity"), field("start"), field("end")) else {
+                report("missing activity/start/end -> drop".into());
+                changed = true;
+                continue;
+            };
+            let (Ok(st), Ok(en)) = (parse_iso(&st), parse_iso(&en)) else {
+                report("unparseable start/end -> drop".into());
+                changed = true;
+                continue;
+            };
+            if en < st {
+                report("end before start -> drop".into());
+                changed = true;
+                continue;
+            }
+            if field("duration").and_then(|d| parse_duration_seconds(&d)).is_none() {
+                report("missing or bad duration -> recompute from start/end".into());
+                v["duration"] = serde_json::Value::String(dur_iso((en - st).whole_seconds()));
+                changed = true;
+                kept.push_str(&serde_json::to_string(&v)?);
+                kept.push('\n');
+                continue;
+            }
+            kept.push_str(line);
+            kept.push('\n');
+        }
+        if fix && changed {
+            let mut bak = path.as_os_str().to_owned();
+            bak.push(".bak");
+            fs::copy(&path, PathBuf::from(bak))?;
+            rewrite_atomic(&path, &kept)?;
+        }
+    }
+    Ok(issues)
+}
+
+/// Replace a file's contents via temp file + rename (like `active::save`).
+pub fn rewrite_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    write_file(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Move every month file and active.json into `archive/<UTC timestamp>/` in the data dir.
+/// Returns the archive dir and how many files went there, or `None` if there was nothing.
+pub fn archive_all() -> Result<Option<(PathBuf, usize)>> {
+    // held throughout, so no stop/start lands between the move and an empty data dir
+    let _lock = crate::active::Lock::acquire()?;
+    let mut files = track_files();
+    let active = dir().join("active.json");
+    if active.exists() { files.push(active); }
+    if files.is_empty() { return Ok(None); }
+
+    let stamp = crate::util::now_utc()
+        .format(time::macros::format_description!("[year][month][day]T[hour][minute][second]Z"))?;
+    let dest = dir().join("archive").join(stamp);
+    if dest.exists() { return Err(anyhow!("{} already exists; try again in a second", dest.display())); }
+    fs::create_dir_all(&dest)?;
+    for f in &files {
+        if let Some(name) = f.file_name() { fs::rename(f, dest.join(name))?; }
+    }
+    Ok(Some((dest, files.len())))
+}
+
+/// Remove the entry with the latest `end` across all month files.
+/// Returns the removed JSONL line.
+pub fn remove_last_entry() -> Result<Option<String>> {
+    let mut latest: Option<(OffsetDateTime, PathBuf, usize)> = None;
+    for path in track_files() {
+        let s = fs::read_to_string(&path)?;
+        for (i, line) in s.lines().enumerate() {
+            let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else { continue; };
+            let Some(end) = v.get("end").and_then(|x| x.as_str()).and_then(|e| parse_iso(e).ok()) else { continue; };
+            if latest.as_ref().is_none_or(|(t, _, _)| end >= *t) {
+                latest = Some((end, path.clone(), i));
+            }
+        }
+    }
+    let Some((_, path, idx)) = latest else { return Ok(None); };
+
+    let s = fs::read_to_string(&path)?;
+    let mut removed = None;
+    let mut kept = String::new();
+    for (i, line) in s.lines().enumerate() {
+        if i == idx { removed = Some(line.to_string()); continue; }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    rewrite_atomic(&path, &kept)?;
+    Ok(removed)
+}
+
+/// Field changes for `edit`; `None` keeps the current value.
+pub struct EntryEdit {
+    pub end: Option<String>,
+    pub tag: Option<String>,
+    /// Empty string removes the note.
+    pub note: Option<String>,
+}
+
+/// Apply `edit` to the entry starting at `start` (same instant, any offset) and rewrite
+/// its month file atomically. A new end recomputes the duration as end - start.
+/// Returns the updated JSONL line.
+pub fn edit_entry(start: &str, edit: &EntryEdit) -> Result<String> {
+    let want = parse_iso(start)?;
+    let mut found: Vec<(PathBuf, usize)> = Vec::new();
+    for path in track_files() {
+        let s = fs::read_to_string(&path)?;
+        for (i, line) in s.lines().enumerate() {
+            let Ok(v) = serde_json::from_str::<serde_json::Value>(line) else { continue; };
+            let st = v.get("start").and_then(|x| x.as_str()).and_then(|x| parse_iso(x).ok());
+            if st == Some(want) { found.push((path.clone(), i)); }
+        }
+    }
+    let (path, idx) = match found.len() {
+        0 => return Err(anyhow!("no entry starts at {start} (see `blazectl list`)")),
+        1 => found.remove(0),
+        n => return Err(anyhow!("{n} entries start at {start}; fix them by hand or with `doctor`")),
+    };
+
+    let s = fs::read_to_string(&path)?;
+    let mut out = String::new();
+    let mut updated = String::new();
+    for (i, line) in s.lines().enumerate() {
+        if i != idx {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let mut v: serde_json::Value = serde_json::from_str(line)?;
+        if let Some(tag) = &edit.tag {
+            if tag.trim().is_empty() { return Err(anyhow!("empty tag")); }
+            v["activity"] = tag.clone().into();
+        }
+        if let Some(end) = &edit.end {
+            let en = parse_iso(end)?;
+            if en <= want { return Err(anyhow!("end must be after start ({start})")); }
+            v["end"] = iso(en).into();
+            v["duration"] = dur_iso((en - want).whole_seconds()).into();
+        }
+        match edit.note.as_deref() {
+            Some("") => if let Some(o) = v.as_object_mut() { o.remove("note"); },
+            Some(n) => v["note"] = n.into(),
+            None => {}
+        }
+        updated = serde_json::to_string(&v)?;
+        out.push_str(&updated);
+        out.push('\n');
+    }
+    rewrite_atomic(&path, &out)?;
+    Ok(updated)
+}
+
+/// Rename `old` to `new` in every month file, rewriting only files that change.
+/// Returns the number of entries renamed.
+pub fn rename_tag(old: &str, new: &str) -> Result<usize> {
+    let mut renamed = 0;
+    for path in track_files() {
+        let s = fs::read_to_string(&path)?;
+        let mut out = String::new();
+        let mut changed = false;
+        for line in s.lines() {
+            match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(mut v) if v.get("activity").and_then(|x| x.as_str()) == Some(old) => {
+                    v["activity"] = new.into();
+                    out.push_str(&serde_json::to_string(&v)?);
+                    renamed += 1;
+                    changed = true;
+                }
+                _ => out.push_str(line),
+            }
+            out.push('\n');
+        }
+        if changed { rewrite_atomic(&path, &out)?; }
+    }
+    Ok(renamed)
+}
+
+/// Append into the month file of the entry's start date. Start and end are written
+/// in UTC, and the month is the UTC one.
 pub fn append_entry(e: &Entry) -> Result<()> {
-    let path = month_file(now_utc());
+    let start = parse_iso(&e.start)?.to_offset(UtcOffset::UTC);
+    let utc = Entry {
+        activity: e.activity.clone(),
+        start: iso(start),
+        end: iso(parse_iso(&e.end)?.to_offset(UtcOffset::UTC)),
+        duration: e.duration,
+        note: e.note.clone(),
+        v: e.v,
+    };
+    let path = month_file(start);
     let mut f = OpenOptions::new().create(true).append(true).open(&path)?;
-    let line = serde_json::to_string(e)? + "\n";
+    let line = serde_json::to_string(&utc)? + "\n";
     f.write_all(line.as_bytes())?;
     f.flush()?;
-    // If you want stronger durability, uncomment:
-    // f.sync_all()?;
+    if sync_enabled() { f.sync_all()?; }
+    crate::util::trace(format_args!("appended {} entry to {}", e.activity, path.display()));
     Ok(())
 }
+
+/// Build a completed entry from a start plus either an end or an ISO-8601 duration.
+/// Times given with an offset are stored in UTC, like everything else in the log.
+pub fn manual_entry(tag: &str, start: &str, end: Option<&str>, duration: Option<&str>) -> Result<Entry> {
+    let st = parse_iso(start)?.to_offset(UtcOffset::UTC);
+    let en = match (end, duration) {
+        (Some(e), _) => parse_iso(e)?.to_offset(UtcOffset::UTC),
+        (None, Some(d)) => {
+            let secs = parse_duration_seconds(d).ok_or_else(|| anyhow!("invalid duration: {d}"))?;
+            st + Duration::seconds(secs)
+        }
+        (None, None) => return Err(anyhow!("need --end or --duration")),
+    };
+    if en <= st { return Err(anyhow!("start must be before end")); }
+    Ok(Entry {
+        activity: tag.to_string(),
+        start: iso(st),
+        end: iso(en),
+        duration: en - st,
+        note: None,
+        v: FORMAT_VERSION,
+    })
+}
@@ -1,4 +1,198 @@
-use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use std::sync::{Mutex, OnceLock, atomic::{AtomicBool, Ordering}};
+use time::{Date, OffsetDateTime, UtcOffset, format_description::well_known::Rfc3339, macros::format_description};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+static EMIT_EVENT: AtomicBool = AtomicBool::new(false);
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static CAPTURE: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// `watch`: while on, output lines are held for its status area instead of being
+/// printed over the raw-mode screen.
+pub fn set_capture(on: bool) {
+    *CAPTURE.lock().unwrap_or_else(|e| e.into_inner()) = on.then(Vec::new);
+}
+
+/// Take the lines held since the last call (empty when not capturing).
+pub fn captured() -> Vec<String> {
+    CAPTURE.lock().unwrap_or_else(|e| e.into_inner()).as_mut().map(std::mem::take).unwrap_or_default()
+}
+
+/// The one place output lines are written: held while capturing, else stdout/stderr.
+fn print(stderr: bool, msg: impl std::fmt::Display) {
+    if let Some(held) = CAPTURE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        held.push(msg.to_string());
+    } else if stderr {
+        eprintln!("{msg}");
+    } else {
+        println!("{msg}");
+    }
+}
+
+/// `--dry-run`: render into a temp dir and only print git commands.
+pub fn set_dry_run(on: bool) { DRY_RUN.store(on, Ordering::Relaxed); }
+
+pub fn dry_run() -> bool { DRY_RUN.load(Ordering::Relaxed) }
+
+/// `--emit-event`: one JSON line on stdout per start/stop/cancel.
+pub fn set_emit_event(on: bool) { EMIT_EVENT.store(on, Ordering::Relaxed); }
+
+fn emitting() -> bool { EMIT_EVENT.load(Ordering::Relaxed) }
+
+/// Print `{"event":..,"tag":..,"time":..}` (plus `duration_seconds` for stops) when
+/// `--emit-event` is on.
+pub fn emit_event(event: &str, tag: &str, time: &str, duration_seconds: Option<i64>) {
+    if !emitting() { return; }
+    let mut v = serde_json::json!({ "event": event, "tag": tag, "time": time });
+    if let Some(d) = duration_seconds { v["duration_seconds"] = d.into(); }
+    print(false, v);
+}
+
+/// `--no-color`: never emit ANSI codes.
+pub fn set_no_color(on: bool) { NO_COLOR.store(on, Ordering::Relaxed); }
+
+/// The one color decision: off with `--no-color`, a non-empty `NO_COLOR` env var, or
+/// when stdout isn't a terminal.
+pub fn color() -> bool {
+    use std::io::IsTerminal;
+    !NO_COLOR.load(Ordering::Relaxed)
+        && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        && std::io::stdout().is_terminal()
+}
+
+#[derive(Clone, Copy)]
+pub enum Style { Bold, Dim, Green, Yellow }
+
+/// `text` wrapped in the ANSI codes for `style`, or as-is when `color()` is off.
+pub fn paint(style: Style, text: impl std::fmt::Display) -> String {
+    if !color() { return text.to_string(); }
+    let code = match style {
+        Style::Bold => "1",
+        Style::Dim => "2",
+        Style::Green => "32",
+        Style::Yellow => "33",
+    };
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+/// `warning: msg` on stderr.
+pub fn warn(msg: impl std::fmt::Display) {
+    print(true, format_args!("{}: {msg}", paint(Style::Yellow, "warning")));
+}
+
+/// A non-fatal error line on stderr (a failed render or commit after logging).
+pub fn error(msg: impl std::fmt::Display) { print(true, msg); }
+
+/// `--quiet`: drop the informational messages that go through `info`.
+pub fn set_quiet(on: bool) { QUIET.store(on, Ordering::Relaxed); }
+
+pub fn quiet() -> bool { QUIET.load(Ordering::Relaxed) }
+
+/// Print a progress/confirmation line ("Logged ...", "Already running ...") via `say`
+/// unless `--quiet`. Errors, warnings and requested output bypass this.
+pub fn info(msg: impl std::fmt::Display) {
+    if !quiet() { say(msg); }
+}
+
+/// A human-readable line (infos, dry-run reports) on stdout, or on stderr under
+/// `--emit-event` so stdout carries nothing but event lines.
+pub fn say(msg: impl std::fmt::Display) {
+    print(emitting(), msg);
+}
+
+/// `--verbose`: trace git and file operations on stderr.
+pub fn set_verbose(on: bool) { VERBOSE.store(on, Ordering::Relaxed); }
+
+/// Print `msg` to stderr when `--verbose` is on.
+pub fn trace(msg: impl std::fmt::Display) {
+    if VERBOSE.load(Ordering::Relaxed) { print(true, format_args!("[blazectl] {msg}")); }
+}
+
+/// Zone for day buckets and printed times (config `timezone`); storage stays UTC.
+#[derive(Clone, Copy)]
+pub enum Zone {
+    Fixed(UtcOffset),
+    Named(chrono_tz::Tz),
+}
+
+static ZONE: OnceLock<Zone> = OnceLock::new();
+
+pub fn set_zone(z: Zone) { let _ = ZONE.set(z); }
+
+fn zone() -> Zone { *ZONE.get_or_init(|| Zone::Fixed(UtcOffset::UTC)) }
+
+/// `UTC`, a fixed offset (`+05:30`, `-0800`, `+01`) or an IANA name (`Europe/Berlin`).
+pub fn parse_zone(s: &str) -> anyhow::Result<Zone> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("utc") || s == "Z" {
+        return Ok(Zone::Fixed(UtcOffset::UTC));
+    }
+    if let Some(rest) = s.strip_prefix(['+', '-']) {
+        let digits = rest.replace(':', "");
+        let sign = if s.starts_with('-') { -1 } else { 1 };
+        let hm = match digits.len() {
+            2 => digits.parse::<i8>().ok().map(|h| (h, 0)),
+            4 => digits[..2].parse::<i8>().ok().zip(digits[2..].parse::<i8>().ok()),
+            _ => None,
+        };
+        return hm
+            .and_then(|(h, m)| UtcOffset::from_hms(sign * h, sign * m, 0).ok())
+            .map(Zone::Fixed)
+            .ok_or_else(|| anyhow::anyhow!("invalid UTC offset: {s} (use +HH:MM)"));
+    }
+    s.parse::<chrono_tz::Tz>()
+        .map(Zone::Named)
+        .map_err(|_| anyhow::anyhow!("unknown timezone: {s} (use UTC, +HH:MM or an IANA name)"))
+}
+
+/// Offset of the configured zone at instant `t` (DST-aware for IANA names).
+fn offset_at(t: OffsetDateTime) -> UtcOffset {
+    match zone() {
+        Zone::Fixed(o) => o,
+        Zone::Named(tz) => {
+            use chrono::{Offset, TimeZone};
+            let utc = chrono::DateTime::from_timestamp(t.unix_timestamp(), 0).unwrap_or_default().naive_utc();
+            UtcOffset::from_whole_seconds(tz.offset_from_utc_datetime(&utc).fix().local_minus_utc())
+                .unwrap_or(UtcOffset::UTC)
+        }
+    }
+}
+
+/// `t` in the configured zone.
+pub fn local(t: OffsetDateTime) -> OffsetDateTime {
+    t.to_offset(offset_at(t))
+}
+
+/// Today's date in the configured zone.
+pub fn today() -> Date {
+    local(now_utc()).date()
+}
+
+/// The instant local day `d` begins (00:00 in the configured zone).
+pub fn day_start(d: Date) -> OffsetDateTime {
+    let naive = d.midnight();
+    // the offset at local midnight may differ from the one at UTC midnight (DST); refine once
+    let guess = naive.assume_offset(offset_at(naive.assume_utc()));
+    naive.assume_offset(offset_at(guess)).to_offset(UtcOffset::UTC)
+}
+
+/// Short zone name for labels: `UTC`, `+05:30`, `Europe/Berlin`.
+pub fn zone_label() -> String {
+    match zone() {
+        Zone::Fixed(o) if o.is_utc() => "UTC".into(),
+        Zone::Fixed(o) => {
+            let (h, m, _) = o.as_hms();
+            format!("{}{:02}:{:02}", if o.is_negative() { '-' } else { '+' }, h.abs(), m.abs())
+        }
+        Zone::Named(tz) => tz.name().into(),
+    }
+}
+
+/// An RFC3339 timestamp re-expressed in the configured zone (unchanged if unparsable).
+pub fn local_iso(s: &str) -> String {
+    parse_iso(s).map(|t| iso(local(t))).unwrap_or_else(|_| s.to_string())
+}
 
 pub fn now_utc() -> OffsetDateTime {
     OffsetDateTime::now_utc()
@@ -11,3 +205,97 @@ pub fn iso(dt: OffsetDateTime) -> String {
 pub fn parse_iso(s: &str) -> anyhow::Result<OffsetDateTime> {
     Ok(OffsetDateTime::parse(s, &Rfc3339)?)
 }
+
+/// `YYYY-MM-DD`, or the (local) date of an RFC3339 timestamp.
+pub fn parse_date(s: &str) -> anyhow::Result<Date> {
+    if let Ok(d) = Date::parse(s, format_description!("[year]-[month]-[day]")) {
+        return Ok(d);
+    }
+    parse_iso(s)
+        .map(|t| local(t).date())
+        .map_err(|_| anyhow::anyhow!("invalid date: {s} (use YYYY-MM-DD)"))
+}
+
+/// Second-precision local `YYYY-MM-DD HH:MM:SS` for tables.
+pub fn short_ts(s: &str) -> String {
+    match parse_iso(s) {
+        Ok(t) => local(t)
+            .format(format_description!("[year]-[month]-[day] [hour]:[minute]:[second]"))
+            .unwrap_or_else(|_| s.to_string()),
+        Err(_) => s.to_string(),
+    }
+}
+
+/// How long ago an RFC3339 timestamp was: `just now`, `14m ago`, `2h 14m ago`, `3d 4h ago`.
+/// Empty if `s` doesn't parse.
+pub fn ago(s: &str) -> String {
+    let Ok(t) = parse_iso(s) else { return String::new(); };
+    let secs = (now_utc() - t).whole_seconds();
+    let (d, h, m) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if secs < 60 {
+        "just now".into()
+    } else if secs < 3600 {
+        format!("{m}m ago")
+    } else if secs < 86400 {
+        format!("{h}h {m:02}m ago")
+    } else {
+        format!("{d}d {h}h ago")
+    }
+}
+
+pub fn hm(secs: i64) -> String {
+    let h = secs / 3600;
+    let m = (secs % 3600) / 60;
+    format!("{h}h {m:02}m")
+}
+
+/// Like `hm` but keeps short durations visible: `45s`, `12m`, `1h 05m` (`0m` for nothing).
+pub fn hms(secs: i64) -> String {
+    match secs {
+        s if s <= 0 => "0m".to_string(),
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s => hm(s),
+    }
+}
+
+/// Seconds in a short span like `50m`, `1h30m`, `90s`, or an ISO-8601 duration (`PT50M`).
+pub fn parse_span_seconds(s: &str) -> Option<i64> {
+    let s = s.trim().to_ascii_uppercase();
+    if s.starts_with('P') { return parse_duration_seconds(&s); }
+    parse_duration_seconds(&format!("PT{s}"))
+}
+
+/// Seconds in an ISO-8601 duration: `PT1H2M3S`, `P1DT2H`, `PT0.25H`, `PT1.5S`.
+/// Fractions are rounded to whole seconds; `None` if the string isn't a duration.
+pub fn parse_duration_seconds(iso: &str) -> Option<i64> {
+    let s = iso.trim().strip_prefix('P')?;
+    let mut total = 0.0f64;
+    let mut in_time = false;
+    let mut any = false;
+    let mut num = String::new();
+    for ch in s.chars() {
+        if ch.is_ascii_digit() || ch == '.' || ch == ',' {
+            num.push(if ch == ',' { '.' } else { ch });
+            continue;
+        }
+        if ch == 'T' {
+            if in_time || !num.is_empty() { return None; }
+            in_time = true;
+            continue;
+        }
+        let val = num.parse::<f64>().ok()?;
+        let unit = match (in_time, ch) {
+            (false, 'D') => 86400.0,
+            (true, 'H')  => 3600.0,
+            (true, 'M')  => 60.0,
+            (true, 'S')  => 1.0,
+            _ => return None,
+        };
+        total += val * unit;
+        any = true;
+        num.clear();
+    }
+    if !any || !num.is_empty() { return None; }
+    Some(total.round() as i64)
+}
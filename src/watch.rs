@@ -0,0 +1,155 @@
+use std::{io::{stdout, Write}, time::Duration};
+use anyhow::Result;
+use crate::util::{paint, Style};
+use crossterm::{
+    cursor, event::{self, Event, KeyCode, KeyEvent, KeyModifiers}, execute, queue,
+    terminal::{self, ClearType},
+};
+
+/// Alternate screen + raw mode for `blazectl watch`; restored however `run` exits.
+/// Output lines are captured meanwhile (`util::set_capture`) and shown in the frame.
+struct Screen;
+
+impl Screen {
+    fn enter() -> Result<Screen> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        crate::util::set_capture(true);
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        crate::util::set_capture(false);
+        let _ = execute!(stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Captured lines kept on screen.
+const LOG_LINES: usize = 5;
+
+/// `1:02:03`
+fn clock(secs: i64) -> String {
+    let s = secs.max(0);
+    format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+}
+
+/// One screenful; re-reads active.json and the logs every time. `log` holds the latest
+/// messages (infos, git, events) that would otherwise go to stdout/stderr.
+fn frame(status: &str, log: &[String]) -> Result<String> {
+    use std::fmt::Write;
+    let mut s = String::new();
+    let running = crate::active::running()?;
+    if running.is_empty() {
+        writeln!(s, "No active session.")?;
+    }
+    let now = crate::util::now_utc();
+    for r in &running {
+        let mut state = if r.paused_since.is_some() { paint(Style::Dim, "  (paused)") } else { String::new() };
+        if let Some(end) = r.planned_end.as_deref().and_then(|e| crate::util::parse_iso(e).ok()) {
+            state.push_str(&format!("  {} left", clock((end - now).whole_seconds())));
+        }
+        writeln!(s, "{} {}{state}", paint(Style::Bold, format!("{:<10}", r.tag)), clock(r.elapsed.whole_seconds()))?;
+    }
+    writeln!(s)?;
+    writeln!(s, "{}", crate::readme::today(&crate::active::live_today()?)?)?;
+    writeln!(s)?;
+    for line in log {
+        // a multi-line message would break the raw-mode layout
+        for l in line.lines() { writeln!(s, "{}", paint(Style::Dim, l))?; }
+    }
+    if !log.is_empty() { writeln!(s)?; }
+    if !status.is_empty() { writeln!(s, "{status}")?; }
+    write!(s, "q / Ctrl-C to quit")?;
+    Ok(s)
+}
+
+fn quit(k: &KeyEvent) -> bool {
+    matches!(k.code, KeyCode::Char('q') | KeyCode::Esc)
+        || (k.code == KeyCode::Char('c') && k.modifiers.contains(KeyModifiers::CONTROL))
+}
+
+/// Run until q / Ctrl-C. Sessions reaching their `start --for` end are stopped and
+/// handed to `finished` (log, events, README). With `idle_minutes`, running sessions are
+/// paused once the desktop has been idle that long and resumed on the next input.
+pub fn run(
+    finished: &dyn Fn(&[crate::store::Entry]) -> Result<()>,
+    #[cfg(feature = "idle")] idle_minutes: Option<u64>,
+) -> Result<()> {
+    #[cfg(feature = "idle")]
+    if idle_minutes.is_some() { idle::seconds()?; } // fail before taking over the terminal
+    #[cfg(feature = "idle")]
+    let mut auto_paused = std::collections::HashSet::new();
+    let _screen = Screen::enter()?;
+    let mut out = stdout();
+    let mut last_done = String::new();
+    let mut log: Vec<String> = Vec::new();
+    loop {
+        let mut status = String::new();
+        #[cfg(feature = "idle")]
+        if let Some(m) = idle_minutes { status = idle::tick(m, &mut auto_paused)?; }
+        let done = crate::active::finish_due()?;
+        if !done.is_empty() { finished(&done)?; }
+        if let Some(e) = done.last() { last_done = format!("stopped {} at its planned end", e.activity); }
+        if status.is_empty() { status.clone_from(&last_done); }
+        log.extend(crate::util::captured());
+        log.drain(..log.len().saturating_sub(LOG_LINES));
+        let text = frame(&status, &log)?;
+        queue!(out, cursor::MoveTo(0, 0), terminal::Clear(ClearType::All))?;
+        // raw mode: no implicit carriage return
+        for line in text.lines() {
+            write!(out, "{line}\r\n")?;
+        }
+        out.flush()?;
+        if event::poll(Duration::from_secs(1))? {
+            if let Event::Key(k) = event::read()? {
+                if quit(&k) { return Ok(()); }
+            }
+        }
+    }
+}
+
+/// `--auto-pause`: X11 idle time via `xprintidle`.
+#[cfg(feature = "idle")]
+mod idle {
+    use std::collections::HashSet;
+    use anyhow::{anyhow, Result};
+    use time::Duration;
+
+    /// Seconds since the last keyboard/mouse input.
+    pub fn seconds() -> Result<u64> {
+        let out = std::process::Command::new("xprintidle").output()
+            .map_err(|e| anyhow!("--auto-pause needs `xprintidle` on PATH: {e}"))?;
+        if !out.status.success() {
+            return Err(anyhow!("xprintidle failed: {}", String::from_utf8_lossy(&out.stderr).trim()));
+        }
+        let ms: u64 = String::from_utf8_lossy(&out.stdout).trim().parse()
+            .map_err(|_| anyhow!("unexpected xprintidle output"))?;
+        Ok(ms / 1000)
+    }
+
+    /// Pause running sessions from when idling began once idle for `minutes`; resume the
+    /// ones paused here (never a manual pause) when input comes back. Returns a status line.
+    pub fn tick(minutes: u64, auto_paused: &mut HashSet<String>) -> Result<String> {
+        let idle = seconds()?;
+        let running = crate::active::running()?;
+        if idle >= minutes * 60 {
+            let since = crate::util::now_utc() - Duration::seconds(idle as i64);
+            for r in running.iter().filter(|r| r.paused_since.is_none()) {
+                crate::active::pause_at(&r.tag, since)?;
+                auto_paused.insert(r.tag.clone());
+            }
+            if !auto_paused.is_empty() {
+                return Ok(format!("auto-paused after {} idle", crate::util::hms(idle as i64)));
+            }
+        } else {
+            for r in running.iter().filter(|r| r.paused_since.is_some() && auto_paused.contains(&r.tag)) {
+                crate::active::resume(&r.tag)?;
+            }
+            auto_paused.clear();
+        }
+        Ok(format!("auto-pause after {minutes}m idle"))
+    }
+}
@@ -0,0 +1,36 @@
+use blazectl::{active, gitops, readme, store, util};
+use std::process::Command;
+use time::Duration;
+
+fn git(root: &std::path::Path, args: &[&str]) -> String {
+    let out = Command::new("git").current_dir(root).args(args).output().unwrap();
+    assert!(out.status.success(), "git {args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8_lossy(&out.stdout).into_owned()
+}
+
+// store::set_dir is set-once per process, so this file holds one repo.
+#[test]
+fn stop_commit_includes_the_svgs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    git(root, &["init", "-q"]);
+    git(root, &["config", "user.email", "test@example.com"]);
+    git(root, &["config", "user.name", "test"]);
+    store::set_dir(root.join(".blaze"));
+    store::ensure_dirs().unwrap();
+
+    // what `blazectl stop` does: close, append, render, commit
+    let start = util::iso(util::now_utc() - Duration::minutes(30));
+    active::start("train", Some(&start), None, None).unwrap().expect("started");
+    let entry = active::stop("train", false).unwrap().expect("was running");
+    store::append_entry(&entry).unwrap();
+    let md = readme::render_all(&readme::Palette::dark(), &readme::RenderOptions::default()).unwrap();
+    readme::write_readme(&md).unwrap();
+    gitops::auto_commit_if_due(false).unwrap();
+
+    let stat = git(root, &["show", "--stat", "--format=", "HEAD"]);
+    for file in ["README.md", "assets/activity.svg", "assets/heatmap.svg", "assets/hours.svg"] {
+        assert!(stat.contains(file), "{file} not committed:\n{stat}");
+    }
+    assert!(git(root, &["status", "--porcelain"]).trim().is_empty(), "left uncommitted changes");
+}
@@ -0,0 +1,19 @@
+use blazectl::{active, store};
+
+// store::set_dir is set-once per process, so this file holds one data dir.
+#[test]
+fn garbage_active_json_is_moved_aside() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().join(".blaze");
+    store::set_dir(dir.clone());
+    store::ensure_dirs().unwrap();
+    std::fs::write(dir.join("active.json"), "garbage{").unwrap();
+
+    assert!(active::status().unwrap().is_empty());
+    assert_eq!(std::fs::read_to_string(dir.join("active.json.bad")).unwrap(), "garbage{");
+    assert!(!dir.join("active.json").exists());
+
+    // and the data dir is usable again
+    assert!(active::start("train", None, None, None).unwrap().is_some());
+    assert_eq!(active::status().unwrap().len(), 1);
+}
@@ -0,0 +1,107 @@
+use blazectl::{aggregate, days_back, streaks, LoggedEntry};
+use time::{macros::{date, datetime}, Date, Duration};
+
+/// One hour of `tag` at 10:00 UTC on each day.
+fn daily(tag: &str, days: &[Date]) -> Vec<LoggedEntry> {
+    days.iter()
+        .map(|d| {
+            let start = d.with_hms(10, 0, 0).unwrap().assume_utc();
+            LoggedEntry {
+                activity: tag.into(),
+                start,
+                end: Some(start + Duration::hours(1)),
+                duration_seconds: 3600,
+                note: None,
+            }
+        })
+        .collect()
+}
+
+fn streak<'a>(rows: &'a [(String, i32, i32)], label: &str) -> &'a (String, i32, i32) {
+    rows.iter().find(|r| r.0 == label).unwrap()
+}
+
+#[test]
+fn windows_cross_the_year_boundary() {
+    let w = days_back(date!(2026 - 01 - 03), 7);
+    assert_eq!(w.len(), 7);
+    assert_eq!(w[0], date!(2025 - 12 - 28));
+    assert_eq!(w[6], date!(2026 - 01 - 03));
+    assert!(w.contains(&date!(2025 - 12 - 31)) && w.contains(&date!(2026 - 01 - 01)));
+
+    for n in [7, 30, 75] {
+        let w = days_back(date!(2026 - 01 - 10), n);
+        assert_eq!(w.len(), n as usize);
+        assert_eq!(*w.last().unwrap(), date!(2026 - 01 - 10));
+        assert!(w.windows(2).all(|p| p[0].next_day() == Some(p[1])), "{n}-day window has a gap");
+    }
+    assert_eq!(days_back(date!(2026 - 01 - 10), 30)[0], date!(2025 - 12 - 12));
+    assert_eq!(days_back(date!(2026 - 01 - 10), 75)[0], date!(2025 - 10 - 28));
+}
+
+#[test]
+fn windows_handle_leap_days() {
+    assert_eq!(
+        days_back(date!(2024 - 03 - 01), 3),
+        [date!(2024 - 02 - 28), date!(2024 - 02 - 29), date!(2024 - 03 - 01)],
+    );
+    assert_eq!(
+        days_back(date!(2023 - 03 - 01), 3),
+        [date!(2023 - 02 - 27), date!(2023 - 02 - 28), date!(2023 - 03 - 01)],
+    );
+    assert_eq!(days_back(date!(2024 - 03 - 30), 30)[0], date!(2024 - 03 - 01));
+    assert_eq!(days_back(date!(2024 - 03 - 29), 30)[0], date!(2024 - 02 - 29));
+}
+
+#[test]
+fn streaks_run_across_new_year() {
+    let days = days_back(date!(2026 - 01 - 02), 5); // Dec 29 .. Jan 2
+    let (_, per_day) = aggregate(&daily("train", &days));
+
+    let rows = streaks(&per_day, date!(2026 - 01 - 02));
+    assert_eq!(streak(&rows, "any"), &("any".to_string(), 5, 5));
+    assert_eq!(streak(&rows, "train"), &("train".to_string(), 5, 5));
+
+    // a day later with nothing logged: current resets, longest stays
+    let rows = streaks(&per_day, date!(2026 - 01 - 03));
+    assert_eq!(streak(&rows, "any").1, 0);
+    assert_eq!(streak(&rows, "any").2, 5);
+}
+
+// current streaks aren't capped at a year
+#[test]
+fn streaks_longer_than_a_year() {
+    let days = days_back(date!(2026 - 10 - 14), 400);
+    let (_, per_day) = aggregate(&daily("train", &days));
+    assert_eq!(streak(&streaks(&per_day, date!(2026 - 10 - 14)), "train"), &("train".to_string(), 400, 400));
+}
+
+#[test]
+fn streaks_run_across_leap_day() {
+    let leap = [date!(2024 - 02 - 27), date!(2024 - 02 - 28), date!(2024 - 02 - 29), date!(2024 - 03 - 01)];
+    let (_, per_day) = aggregate(&daily("battle", &leap));
+    assert_eq!(streak(&streaks(&per_day, date!(2024 - 03 - 01)), "battle").1, 4);
+
+    // without Feb 29 the run is broken
+    let (_, per_day) = aggregate(&daily("battle", &[leap[0], leap[1], leap[3]]));
+    let rows = streaks(&per_day, date!(2024 - 03 - 01));
+    assert_eq!(streak(&rows, "battle").1, 1);
+    assert_eq!(streak(&rows, "battle").2, 2);
+}
+
+#[test]
+fn sessions_over_midnight_split_into_both_years() {
+    let start = datetime!(2025-12-31 23:00 UTC);
+    let e = LoggedEntry {
+        activity: "train".into(),
+        start,
+        end: Some(start + Duration::hours(2)),
+        duration_seconds: 7200,
+        note: None,
+    };
+    let (all, per_day) = aggregate(&[e]);
+    assert_eq!(all.total(), 7200);
+    assert_eq!(per_day[&date!(2025 - 12 - 31)].get("train"), 3600);
+    assert_eq!(per_day[&date!(2026 - 01 - 01)].get("train"), 3600);
+    assert_eq!(streak(&streaks(&per_day, date!(2026 - 01 - 01)), "any").1, 2);
+}
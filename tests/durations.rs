@@ -0,0 +1,42 @@
+use blazectl::{store::dur_iso, util::parse_duration_seconds};
+
+#[test]
+fn parses_exact_seconds() {
+    assert_eq!(parse_duration_seconds("PT1H30M0S"), Some(5400));
+    assert_eq!(parse_duration_seconds("PT90M"), Some(5400));
+    assert_eq!(parse_duration_seconds("PT45S"), Some(45));
+    assert_eq!(parse_duration_seconds("P1DT2H"), Some(26 * 3600));
+    assert_eq!(parse_duration_seconds("P2D"), Some(2 * 86400));
+    assert_eq!(parse_duration_seconds("PT0.25H"), Some(900));
+    assert_eq!(parse_duration_seconds("PT1,5S"), Some(2)); // rounded
+    assert_eq!(parse_duration_seconds(" PT0H0M0S "), Some(0));
+}
+
+// malformed input is rejected rather than silently counted as 0s
+#[test]
+fn rejects_malformed_input() {
+    for bad in ["", "garbage", "P", "PT", "PT5", "PT1H30", "1H30M", "PT1X", "PT1D", "P1H", "PTT1H", "P1DT2HT"] {
+        assert_eq!(parse_duration_seconds(bad), None, "{bad:?}");
+    }
+}
+
+#[test]
+fn day_long_durations_round_trip() {
+    assert_eq!(dur_iso(90000), "P1DT1H0M0S");
+    assert_eq!(parse_duration_seconds(&dur_iso(90000)), Some(90000));
+    assert_eq!(dur_iso(5400), "PT1H30M0S");
+    for secs in [0, 59, 86399, 86400, 3 * 86400 + 61] {
+        assert_eq!(parse_duration_seconds(&dur_iso(secs)), Some(secs), "{secs}s");
+    }
+}
+
+// fractional seconds take either decimal separator, and round to whole seconds
+#[test]
+fn fractional_seconds() {
+    assert_eq!(parse_duration_seconds("PT1.5S"), Some(2));
+    assert_eq!(parse_duration_seconds("PT1,5S"), Some(2));
+    assert_eq!(parse_duration_seconds("PT0.4S"), Some(0));
+    for bad in ["PT", "P1.5.5S", "PT-1S", "PT1.5"] {
+        assert_eq!(parse_duration_seconds(bad), None, "{bad:?}");
+    }
+}
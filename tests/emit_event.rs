@@ -0,0 +1,34 @@
+use std::process::Command;
+
+/// Run the binary with `--emit-event` against `dir` and return its stdout.
+fn blazectl(dir: &std::path::Path, args: &[&str]) -> String {
+    let out = Command::new(env!("CARGO_BIN_EXE_blazectl"))
+        .arg("--emit-event").arg("--dir").arg(dir)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{args:?}: {}", String::from_utf8_lossy(&out.stderr));
+    String::from_utf8(out.stdout).unwrap()
+}
+
+// infos, "Rounded ..." and the render/commit output must not mix into the event stream
+#[test]
+fn stdout_carries_only_json_events() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().join(".blaze");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.toml"), "round_minutes = 15\n").unwrap();
+
+    let at = blazectl::util::iso(blazectl::util::now_utc() - time::Duration::minutes(20));
+    let mut stdout = blazectl(&dir, &["start", "train", "--at", &at]);
+    stdout += &blazectl(&dir, &["stop", "train"]);
+    stdout += &blazectl(&dir, &["start", "read"]);
+    stdout += &blazectl(&dir, &["cancel", "read"]);
+
+    let events: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap_or_else(|e| panic!("not JSON ({e}): {l:?}")))
+        .collect();
+    let kinds: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(kinds, ["start", "stop", "start", "cancel"]);
+}
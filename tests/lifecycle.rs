@@ -0,0 +1,38 @@
+use blazectl::{active, store, util};
+use time::{macros::offset, Duration};
+
+// store::set_dir is set-once per process, so the whole lifecycle is one test.
+#[test]
+fn start_stop_appends_to_the_start_month() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = tmp.path().join(".blaze");
+    store::set_dir(dir.clone());
+    store::ensure_dirs().unwrap();
+
+    let start = util::now_utc() - Duration::seconds(90);
+    // given with an offset, stored in UTC
+    let at = util::iso(start.to_offset(offset!(+2)));
+    let started = active::start("Train", Some(&at), None, None).unwrap().expect("started");
+    assert!(started.stopped.is_empty());
+
+    let running: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("active.json")).unwrap()).unwrap();
+    assert_eq!(running["train"]["start"], util::iso(start));
+
+    let entry = active::stop("train", false).unwrap().expect("was running");
+    store::append_entry(&entry).unwrap();
+    assert!(active::stop("train", false).unwrap().is_none());
+
+    let month = dir.join(format!("track-{}-{:02}.jsonl", start.year(), start.month() as u8));
+    let lines = std::fs::read_to_string(&month).unwrap();
+    let logged: Vec<serde_json::Value> = lines.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(logged.len(), 1);
+    assert_eq!(logged[0]["activity"], "train");
+    assert_eq!(logged[0]["start"], util::iso(start));
+    let secs = util::parse_duration_seconds(logged[0]["duration"].as_str().unwrap()).unwrap();
+    assert!((90..100).contains(&secs), "duration {secs}s");
+
+    let entries = store::read_all_entries().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].duration_seconds, secs);
+}
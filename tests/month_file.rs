@@ -0,0 +1,21 @@
+use blazectl::store;
+
+// store::set_dir is set-once per process, so this file holds one append.
+#[test]
+fn entries_go_to_their_utc_month() {
+    let tmp = tempfile::tempdir().unwrap();
+    store::set_dir(tmp.path().join(".blaze"));
+    store::ensure_dirs().unwrap();
+
+    // 2026-11-01T00:30+02:00 is still October in UTC
+    let mut e = store::manual_entry("train", "2026-10-31T22:30:00Z", None, Some("PT1H")).unwrap();
+    e.start = "2026-11-01T00:30:00+02:00".into();
+    e.end = "2026-11-01T01:30:00+02:00".into();
+    store::append_entry(&e).unwrap();
+
+    assert!(!tmp.path().join(".blaze/track-2026-11.jsonl").exists());
+    let line = std::fs::read_to_string(tmp.path().join(".blaze/track-2026-10.jsonl")).unwrap();
+    let v: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+    assert_eq!(v["start"], "2026-10-31T22:30:00Z");
+    assert_eq!(v["end"], "2026-10-31T23:30:00Z");
+}
@@ -0,0 +1,52 @@
+use blazectl::{readme, store, util};
+use time::Duration;
+
+/// `points` of every `<polyline>` drawn in `color` with `width`, opacity `opacity`.
+fn polylines(svg: &str, color: plotters::style::RGBColor, width: u32, opacity: &str) -> Vec<Vec<(f64, f64)>> {
+    let attrs = format!(
+        r##"opacity="{opacity}" stroke="#{:02X}{:02X}{:02X}" stroke-width="{width}" points=""##,
+        color.0, color.1, color.2,
+    );
+    svg.match_indices(&attrs)
+        .map(|(i, _)| {
+            let pts = &svg[i + attrs.len()..];
+            pts[..pts.find('"').unwrap()]
+                .split_whitespace()
+                .map(|p| {
+                    let (x, y) = p.split_once(',').unwrap();
+                    (x.parse().unwrap(), y.parse().unwrap())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// store::set_dir is set-once per process, so this file holds one render.
+#[test]
+fn uniform_days_draw_a_baseline_and_no_spline() {
+    let tmp = tempfile::tempdir().unwrap();
+    store::set_dir(tmp.path().join(".blaze"));
+    store::ensure_dirs().unwrap();
+
+    // the same minute every day, past the default 75-day window
+    let today = util::day_start(util::today());
+    for i in 0..80 {
+        let start = util::iso(today - Duration::days(i));
+        store::append_entry(&store::manual_entry("train", &start, None, Some("PT1M")).unwrap()).unwrap();
+    }
+
+    readme::render_all(&readme::Palette::dark(), &readme::RenderOptions::default()).unwrap();
+    let svg = std::fs::read_to_string(tmp.path().join("assets/activity.svg")).unwrap();
+    let pal = readme::Palette::dark();
+
+    let baseline = polylines(&svg, pal.text, 1, "0.6");
+    assert_eq!(baseline.len(), 1, "one baseline");
+    assert_eq!(baseline[0].len(), 2);
+    assert_eq!(baseline[0][0].1, baseline[0][1].1, "baseline is horizontal");
+
+    // the trend stays on its moving-average control points: flat, not a dense spline
+    let trend = polylines(&svg, pal.trend, 4, "1");
+    assert_eq!(trend.len(), 1, "one trend line");
+    assert!(trend[0].len() <= 75, "trend has {} points, looks like a spline", trend[0].len());
+    assert!(trend[0].iter().all(|p| p.1 == trend[0][0].1), "trend is flat");
+}
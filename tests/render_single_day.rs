@@ -0,0 +1,20 @@
+use blazectl::{readme, store, util};
+
+// store::set_dir is set-once per process, so this file holds one render.
+#[test]
+fn one_day_of_data_renders_a_finite_chart() {
+    let tmp = tempfile::tempdir().unwrap();
+    store::set_dir(tmp.path().join(".blaze"));
+    store::ensure_dirs().unwrap();
+
+    let start = util::iso(util::day_start(util::today()));
+    store::append_entry(&store::manual_entry("train", &start, None, Some("PT25M")).unwrap()).unwrap();
+
+    let md = readme::render_all(&readme::Palette::dark(), &readme::RenderOptions::default()).unwrap();
+    assert!(md.contains("![Activity Graph]"));
+
+    let svg = std::fs::read_to_string(tmp.path().join("assets/activity.svg")).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(!svg.contains("NaN"), "NaN in the chart");
+    assert!(!svg.contains("inf"), "infinite coordinate in the chart");
+}
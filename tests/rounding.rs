@@ -0,0 +1,38 @@
+use blazectl::config::Config;
+
+fn cfg(minutes: u64, mode: &str) -> Config {
+    Config { round_minutes: minutes, round_mode: mode.into(), ..Config::default() }
+}
+
+#[test]
+fn rounding_is_off_by_default() {
+    assert_eq!(Config::default().round_seconds(487), 487);
+}
+
+#[test]
+fn rounds_to_the_configured_step() {
+    let nearest = cfg(15, "nearest");
+    assert_eq!(nearest.round_seconds(7 * 60), 0);
+    assert_eq!(nearest.round_seconds(7 * 60 + 30), 15 * 60); // halfway goes up
+    assert_eq!(nearest.round_seconds(22 * 60), 15 * 60);
+    assert_eq!(nearest.round_seconds(15 * 60), 15 * 60);
+
+    let up = cfg(5, "up");
+    assert_eq!(up.round_seconds(1), 5 * 60);
+    assert_eq!(up.round_seconds(10 * 60), 10 * 60);
+    assert_eq!(up.round_seconds(0), 0);
+
+    let down = cfg(5, "down");
+    assert_eq!(down.round_seconds(9 * 60 + 59), 5 * 60);
+}
+
+// a stopped session is never logged as 0s, whatever the mode
+#[test]
+fn sessions_round_to_at_least_one_step() {
+    let nearest = cfg(15, "nearest");
+    assert_eq!(nearest.round_session(7 * 60), 15 * 60);
+    assert_eq!(nearest.round_session(22 * 60), 15 * 60);
+    assert_eq!(cfg(5, "down").round_session(3 * 60), 5 * 60);
+    assert_eq!(nearest.round_session(0), 0);
+    assert_eq!(Config::default().round_session(42), 42);
+}
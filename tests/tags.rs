@@ -0,0 +1,19 @@
+use blazectl::active::normalize_tag;
+
+#[test]
+fn tags_are_trimmed_and_lowercased() {
+    assert_eq!(normalize_tag(" Train ").unwrap(), "train");
+    assert_eq!(normalize_tag("BATTLE").unwrap(), "battle");
+    assert_eq!(normalize_tag("\tdeep-work\n").unwrap(), "deep-work");
+}
+
+#[test]
+fn empty_and_reserved_tags_are_rejected() {
+    for bad in ["", "   ", "\t"] {
+        assert!(normalize_tag(bad).is_err(), "{bad:?}");
+    }
+    for reserved in ["paused", "planned_end", " Paused "] {
+        let err = normalize_tag(reserved).unwrap_err().to_string();
+        assert!(err.contains("reserved"), "{reserved:?}: {err}");
+    }
+}